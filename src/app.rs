@@ -0,0 +1,246 @@
+use crate::bot::trading::{MonitorMode, PriceCache, VolumeTracker};
+use solana_client::rpc_client::RpcClient;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::{prelude::*, types::ChatId};
+use tokio::sync::Mutex as TokioMutex;
+
+const DEFAULT_POLL_JITTER: Duration = Duration::from_secs(3);
+const DEFAULT_STREAM_CHANNEL_CAPACITY: usize = 10_000;
+
+/// Categories of alerts that can be routed to different chats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    HotPair,
+    LargeTrade,
+}
+
+/// Which chats receive each kind of alert. Kinds without an explicit route go
+/// to the default chats.
+#[derive(Clone, Debug, Default)]
+pub struct AlertRoutes {
+    default_chats: Vec<ChatId>,
+    routes: HashMap<AlertKind, Vec<ChatId>>,
+}
+
+impl AlertRoutes {
+    pub fn new(default_chats: Vec<ChatId>) -> Self {
+        Self {
+            default_chats,
+            routes: HashMap::new(),
+        }
+    }
+
+    pub fn with_route(mut self, kind: AlertKind, chats: Vec<ChatId>) -> Self {
+        self.routes.insert(kind, chats);
+        self
+    }
+
+    pub fn chats_for(&self, kind: AlertKind) -> &[ChatId] {
+        self.routes.get(&kind).unwrap_or(&self.default_chats)
+    }
+
+    /// Chats an alert of `kind` goes to when monitoring was started from
+    /// `origin`. An explicit route replaces every other recipient; without
+    /// one the default chats and the origin chat receive it.
+    pub fn recipients(&self, kind: AlertKind, origin: ChatId) -> Vec<ChatId> {
+        if let Some(chats) = self.routes.get(&kind) {
+            return chats.clone();
+        }
+        let mut chats = self.default_chats.clone();
+        if !chats.contains(&origin) {
+            chats.push(origin);
+        }
+        chats
+    }
+
+    /// Parses a comma-separated list of chat ids such as `"123,-100456"`.
+    pub fn parse_chat_ids(input: &str) -> Result<Vec<ChatId>, String> {
+        input
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                id.parse::<i64>()
+                    .map(ChatId)
+                    .map_err(|e| format!("Invalid chat id '{}': {}", id, e))
+            })
+            .collect()
+    }
+}
+
+/// State shared between the Telegram command handlers and the background
+/// monitoring task. Cloning is cheap: every field is a handle.
+#[derive(Clone)]
+pub struct AppState {
+    pub bot: Bot,
+    pub alert_routes: AlertRoutes,
+    pub volume_tracker: Arc<TokioMutex<VolumeTracker>>,
+    /// The tracker's primary RPC client, usable without waiting for the
+    /// tracker lock that a running cycle holds.
+    pub rpc_client: Arc<RpcClient>,
+    /// The tracker's price cache, shared for the same reason.
+    pub price_cache: PriceCache,
+    pub is_tracking: Arc<TokioMutex<bool>>,
+    /// Set by `/pause`: the monitoring task stays alive with its
+    /// subscriptions but skips tracking cycles until `/resume`.
+    pub is_paused: Arc<TokioMutex<bool>>,
+    /// Whether a monitoring task is alive, so `/start` never spawns a second
+    /// one. Always locked before `is_tracking`.
+    pub monitor_running: Arc<TokioMutex<bool>>,
+    pub monitor_mode: MonitorMode,
+    /// Random offset of up to ± this much applied to each monitoring sleep so
+    /// multiple instances don't hit the RPC on the same boundary.
+    pub poll_jitter: Duration,
+    /// Send each cycle's alerts of one kind as a single message instead of
+    /// one message per token.
+    pub batch_alerts: bool,
+    /// Streamed signatures buffered between tracking cycles before new ones
+    /// are dropped.
+    pub stream_channel_capacity: usize,
+}
+
+impl AppState {
+    pub fn new(bot: Bot, alert_routes: AlertRoutes, volume_tracker: VolumeTracker) -> Self {
+        Self {
+            bot,
+            alert_routes,
+            rpc_client: volume_tracker.rpc_client(),
+            price_cache: volume_tracker.price_cache(),
+            volume_tracker: Arc::new(TokioMutex::new(volume_tracker)),
+            is_tracking: Arc::new(TokioMutex::new(false)),
+            is_paused: Arc::new(TokioMutex::new(false)),
+            monitor_running: Arc::new(TokioMutex::new(false)),
+            monitor_mode: MonitorMode::default(),
+            poll_jitter: DEFAULT_POLL_JITTER,
+            batch_alerts: false,
+            stream_channel_capacity: DEFAULT_STREAM_CHANNEL_CAPACITY,
+        }
+    }
+
+    /// Turns tracking on and clears any pause. Returns true when no monitoring
+    /// task is alive and the caller must spawn one; it is then marked running.
+    pub async fn begin_monitoring(&self) -> bool {
+        let mut running = self.monitor_running.lock().await;
+        *self.is_tracking.lock().await = true;
+        *self.is_paused.lock().await = false;
+        !std::mem::replace(&mut *running, true)
+    }
+
+    /// Called by the monitoring task before each cycle. Returns false, and
+    /// marks the task as gone, once tracking was turned off.
+    pub async fn continue_monitoring(&self) -> bool {
+        let mut running = self.monitor_running.lock().await;
+        let tracking = *self.is_tracking.lock().await;
+        if !tracking {
+            *running = false;
+        }
+        tracking
+    }
+
+    /// Pauses or resumes a running monitor. Returns false when monitoring is
+    /// not running.
+    pub async fn set_paused(&self, paused: bool) -> bool {
+        if !*self.is_tracking.lock().await {
+            return false;
+        }
+        *self.is_paused.lock().await = paused;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::trading::RAYDIUM_AMM_PROGRAM;
+    use solana_program::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    const CURSOR: &str = "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+
+    fn state_with_cursor(name: &str) -> AppState {
+        let program = Pubkey::from_str(RAYDIUM_AMM_PROGRAM).unwrap();
+        let cursor_file = std::env::temp_dir().join(format!("whale-bot-{}-{}.json", name, std::process::id()));
+        std::fs::write(&cursor_file, serde_json::json!({ program.to_string(): CURSOR }).to_string()).unwrap();
+
+        let mut tracker = VolumeTracker::new("http://localhost:8899", 100.0, 1000.0).with_cursor_file(&cursor_file);
+        tracker.monitored_tokens.insert("mint".to_string());
+        std::fs::remove_file(cursor_file).unwrap();
+        AppState::new(Bot::new("123:test"), AlertRoutes::new(vec![ChatId(1)]), tracker)
+    }
+
+    #[tokio::test]
+    async fn pause_and_resume_keep_tokens_and_cursors() {
+        let state = state_with_cursor("pause");
+        let program = Pubkey::from_str(RAYDIUM_AMM_PROGRAM).unwrap();
+        assert!(!state.set_paused(true).await);
+        assert!(state.begin_monitoring().await);
+
+        assert!(state.set_paused(true).await);
+        assert!(*state.is_paused.lock().await);
+        assert!(state.set_paused(false).await);
+        assert!(!*state.is_paused.lock().await);
+
+        let tracker = state.volume_tracker.lock().await;
+        assert!(tracker.monitored_tokens.contains("mint"));
+        assert_eq!(tracker.cursor_for(&program), Some(CURSOR));
+    }
+
+    #[tokio::test]
+    async fn start_clears_pause_and_spawns_one_monitor() {
+        let state = state_with_cursor("start");
+        assert!(state.begin_monitoring().await);
+        state.set_paused(true).await;
+
+        assert!(!state.begin_monitoring().await);
+        assert!(!*state.is_paused.lock().await);
+
+        *state.is_tracking.lock().await = false;
+        assert!(!state.continue_monitoring().await);
+        assert!(state.begin_monitoring().await);
+    }
+
+    #[test]
+    fn routed_alerts_fan_out_to_the_route_only() {
+        let routes = AlertRoutes::new(vec![ChatId(1), ChatId(2)])
+            .with_route(AlertKind::LargeTrade, vec![ChatId(10), ChatId(11)]);
+
+        assert_eq!(routes.recipients(AlertKind::LargeTrade, ChatId(99)), vec![ChatId(10), ChatId(11)]);
+        assert_eq!(routes.recipients(AlertKind::HotPair, ChatId(99)), vec![ChatId(1), ChatId(2), ChatId(99)]);
+        assert_eq!(routes.recipients(AlertKind::HotPair, ChatId(2)), vec![ChatId(1), ChatId(2)]);
+    }
+
+    #[test]
+    fn unrouted_kinds_use_the_default_chats() {
+        let routes = AlertRoutes::new(vec![ChatId(1), ChatId(2)]);
+        assert_eq!(routes.chats_for(AlertKind::HotPair), &[ChatId(1), ChatId(2)]);
+        assert_eq!(routes.chats_for(AlertKind::LargeTrade), &[ChatId(1), ChatId(2)]);
+
+        let routes = routes.with_route(AlertKind::HotPair, vec![ChatId(-100123)]);
+        assert_eq!(routes.chats_for(AlertKind::HotPair), &[ChatId(-100123)]);
+        assert_eq!(routes.chats_for(AlertKind::LargeTrade), &[ChatId(1), ChatId(2)]);
+    }
+
+    #[test]
+    fn chat_ids_parse_from_a_comma_separated_list() {
+        assert_eq!(AlertRoutes::parse_chat_ids("123,-100456"), Ok(vec![ChatId(123), ChatId(-100456)]));
+        assert_eq!(AlertRoutes::parse_chat_ids(" 7 , 8 ,"), Ok(vec![ChatId(7), ChatId(8)]));
+        assert_eq!(AlertRoutes::parse_chat_ids(""), Ok(Vec::new()));
+
+        let error = AlertRoutes::parse_chat_ids("123,@channel").unwrap_err();
+        assert!(error.starts_with("Invalid chat id '@channel'"));
+    }
+
+    #[test]
+    fn tracker_and_app_state_share_one_rpc_client() {
+        let rpc_client = Arc::new(RpcClient::new("http://localhost:8899".to_string()));
+        let tracker = VolumeTracker::with_rpc_client(Arc::clone(&rpc_client), 100.0, 1000.0)
+            .with_fallback_rpc_urls(vec!["http://localhost:8900".to_string()]);
+        assert!(Arc::ptr_eq(&tracker.rpc_client(), &rpc_client));
+
+        let state = AppState::new(Bot::new("123:test"), AlertRoutes::new(vec![ChatId(1)]), tracker);
+        assert!(Arc::ptr_eq(&state.rpc_client, &rpc_client));
+        assert!(Arc::ptr_eq(&state.volume_tracker.try_lock().unwrap().rpc_client(), &rpc_client));
+    }
+}
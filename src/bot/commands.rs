@@ -7,28 +7,33 @@ pub enum Command {
     Start,
     #[command(description = "Stop monitoring trades")]
     Stop,
+    #[command(description = "Pause monitoring without resetting it")]
+    Pause,
+    #[command(description = "Resume paused monitoring")]
+    Resume,
+    #[command(description = "Check RPC, price API and Telegram connectivity")]
+    Diagnostics,
+    #[command(description = "Show the running build version")]
+    Version,
     #[command(description = "Show current hot trading pairs")]
     HotPairs,
-    #[command(description = "Monitor specific token")]
+    #[command(description = "Monitor a token by symbol or mint address")]
     MonitorToken(String),
-    #[command(description = "Set volume threshold for token")]
-    MonitorTokenVolume(String),  // Accept input as a single string
+    #[command(description = "Set volume threshold for token: <token> <min> <max> <timeframe>", parse_with = "split")]
+    MonitorTokenVolume(String, f64, f64, u64),
+    #[command(description = "Set default volume thresholds for newly added tokens")]
+    SetDefaultVolume(String),
+    #[command(description = "Alert on any single trade above a USD value for a token")]
+    SetSingleTxAlert(String),
+    #[command(description = "Set RPC commitment: confirmed or finalized")]
+    SetCommitment(String),
+    #[command(description = "Suppress alerts below a confidence between 0 and 1")]
+    SetMinConfidence(String),
+    #[command(description = "Rank monitored tokens by volume change since the last cycle")]
+    TopMovers(String),
+    #[command(description = "Clear accumulated volume data, optionally for one token")]
+    ResetVolume(String),
+    #[command(description = "Never monitor or alert on a token mint")]
+    Blacklist(String),
 }
 
-impl Command {
-    pub fn parse_monitor_token_volume(&self) -> Option<(String, f64, f64, u64)> {
-        if let Command::MonitorTokenVolume(input) = self {
-            let parts: Vec<&str> = input.split_whitespace().collect();
-            if parts.len() == 4 {
-                if let (Ok(min), Ok(max), Ok(duration)) = (
-                    parts[1].parse::<f64>(),
-                    parts[2].parse::<f64>(),
-                    parts[3].parse::<u64>(),
-                ) {
-                    return Some((parts[0].to_string(), min, max, duration));
-                }
-            }
-        }
-        None
-    }
-}
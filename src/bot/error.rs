@@ -0,0 +1,119 @@
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_client::nonblocking::pubsub_client::PubsubClientError;
+use teloxide::{ApiError, RequestError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BotError {
+    #[error("Telegram error: {0}")]
+    Telegram(#[from] RequestError),
+    #[error("RPC error: {0}")]
+    Rpc(String),
+    /// The endpoint itself failed: a transport error, a timeout, an HTTP 5xx
+    /// or 429, or a node reporting itself unhealthy. Only these count toward
+    /// failing over to another endpoint.
+    #[error("RPC endpoint unavailable: {0}")]
+    RpcUnavailable(String),
+    #[error("Price API error: {0}")]
+    Price(String),
+    #[error("Token lookup error: {0}")]
+    TokenLookup(String),
+    #[error("Configuration error: {0}")]
+    Config(String),
+    #[error("Dispatcher error: {0}")]
+    Dispatcher(String),
+}
+
+impl BotError {
+    /// Errors that restarting the bot cannot fix: bad configuration, or a
+    /// Telegram rejection such as an invalid token or unreachable chat.
+    pub fn is_fatal(&self) -> bool {
+        match self {
+            BotError::Config(_) => true,
+            BotError::Telegram(e) => is_permanent_telegram_error(e),
+            _ => false,
+        }
+    }
+}
+
+/// Telegram errors that retrying cannot fix, such as an invalid token or a
+/// chat the bot can no longer post to.
+pub fn is_permanent_telegram_error(error: &RequestError) -> bool {
+    match error {
+        RequestError::Api(api_error) => matches!(
+            api_error,
+            ApiError::InvalidToken
+                | ApiError::ChatNotFound
+                | ApiError::BotBlocked
+                | ApiError::BotKicked
+                | ApiError::BotKickedFromSupergroup
+                | ApiError::UserDeactivated
+                | ApiError::CantInitiateConversation
+                | ApiError::NotEnoughRightsToPostMessages
+        ),
+        _ => false,
+    }
+}
+
+impl From<ClientError> for BotError {
+    fn from(error: ClientError) -> Self {
+        if is_endpoint_failure(error.kind()) {
+            BotError::RpcUnavailable(error.to_string())
+        } else {
+            BotError::Rpc(error.to_string())
+        }
+    }
+}
+
+fn is_endpoint_failure(kind: &ClientErrorKind) -> bool {
+    match kind {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(e) => {
+            e.is_connect()
+                || e.is_timeout()
+                || e.is_request()
+                || e.status().is_some_and(|status| status.is_server_error() || status.as_u16() == 429)
+        }
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::NodeUnhealthy { .. },
+            ..
+        }) => true,
+        _ => false,
+    }
+}
+
+impl From<PubsubClientError> for BotError {
+    fn from(error: PubsubClientError) -> Self {
+        BotError::Rpc(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teloxide::types::{ChatId, Seconds};
+
+    #[test]
+    fn only_rejections_retrying_cannot_fix_are_permanent() {
+        assert!(is_permanent_telegram_error(&RequestError::Api(ApiError::InvalidToken)));
+        assert!(is_permanent_telegram_error(&RequestError::Api(ApiError::ChatNotFound)));
+        assert!(is_permanent_telegram_error(&RequestError::Api(ApiError::BotBlocked)));
+
+        assert!(!is_permanent_telegram_error(&RequestError::Api(ApiError::MessageNotModified)));
+        assert!(!is_permanent_telegram_error(&RequestError::Api(ApiError::Unknown("Bad Gateway".to_string()))));
+        assert!(!is_permanent_telegram_error(&RequestError::RetryAfter(Seconds::from_seconds(5))));
+        assert!(!is_permanent_telegram_error(&RequestError::MigrateToChatId(ChatId(-100123))));
+        assert!(!is_permanent_telegram_error(&RequestError::Io(std::io::Error::from(std::io::ErrorKind::ConnectionReset))));
+    }
+
+    #[test]
+    fn config_errors_and_permanent_rejections_are_fatal() {
+        assert!(BotError::Config("missing bot token".to_string()).is_fatal());
+        assert!(BotError::Telegram(RequestError::Api(ApiError::InvalidToken)).is_fatal());
+
+        assert!(!BotError::Telegram(RequestError::RetryAfter(Seconds::from_seconds(5))).is_fatal());
+        assert!(!BotError::Dispatcher("Telegram unreachable".to_string()).is_fatal());
+        assert!(!BotError::RpcUnavailable("connection refused".to_string()).is_fatal());
+    }
+}
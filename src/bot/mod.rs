@@ -1,4 +1,6 @@
 pub mod commands;
+pub mod error;
+pub mod supervisor;
 pub mod telegram;
 pub mod trading;
 
@@ -0,0 +1,103 @@
+use log::{error, info, warn};
+use std::future::Future;
+use std::time::Duration;
+
+const INITIAL_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Spawns `make_task()` and watches it. A panic is logged instead of being
+/// silently dropped with the `JoinHandle`, and when `restart` is set the task
+/// is started again from `make_task` after an exponential backoff. A task that
+/// returns normally is not restarted.
+pub fn spawn_supervised<F, Fut>(name: String, restart: bool, make_task: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut backoff = INITIAL_RESTART_BACKOFF;
+        loop {
+            match tokio::spawn(make_task()).await {
+                Ok(()) => {
+                    info!("Task {} finished", name);
+                    break;
+                }
+                Err(e) if e.is_panic() => {
+                    error!("Task {} panicked: {}", name, panic_message(e.into_panic()));
+                    if !restart {
+                        break;
+                    }
+                    warn!("Restarting task {} in {:?}", name, backoff);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_RESTART_BACKOFF);
+                }
+                Err(e) => {
+                    warn!("Task {} was cancelled: {}", name, e);
+                    break;
+                }
+            }
+        }
+    });
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Spawns a supervised task that panics on its first run only and returns
+    /// a counter of how many times it was started.
+    fn panic_once(restart: bool) -> Arc<AtomicUsize> {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let task_runs = Arc::clone(&runs);
+        spawn_supervised("panics once".to_string(), restart, move || {
+            let runs = Arc::clone(&task_runs);
+            async move {
+                if runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("first run fails");
+                }
+            }
+        });
+        runs
+    }
+
+    #[tokio::test]
+    async fn panicking_task_is_restarted() {
+        let runs = panic_once(true);
+        let restarted = async {
+            while runs.load(Ordering::SeqCst) < 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(INITIAL_RESTART_BACKOFF * 5, restarted).await.unwrap();
+
+        // The second run returned normally, so there is no third.
+        tokio::time::sleep(INITIAL_RESTART_BACKOFF * 3 / 2).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn panicking_task_without_restart_runs_once() {
+        let runs = panic_once(false);
+        tokio::time::sleep(INITIAL_RESTART_BACKOFF * 3 / 2).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn panic_messages_are_extracted_for_the_log() {
+        assert_eq!(panic_message(Box::new("static message")), "static message");
+        assert_eq!(panic_message(Box::new(format!("formatted {}", 42))), "formatted 42");
+        assert_eq!(panic_message(Box::new(7u8)), "unknown panic payload");
+    }
+}
@@ -4,25 +4,57 @@
 use teloxide::{
    prelude::*,
    dispatching::{HandlerExt, UpdateFilterExt},
-   types::ChatId
+   types::{ChatId, Me},
+   utils::command::{BotCommands, ParseError},
+   RequestError,
 };
+use crate::app::{AlertKind, AlertRoutes, AppState};
 use crate::bot::commands::Command;
-use crate::bot::trading::VolumeTracker;
-use std::time::Duration;
-use std::sync::{Arc, Mutex};
+use crate::bot::error::{is_permanent_telegram_error, BotError};
+use crate::bot::supervisor::spawn_supervised;
+use crate::bot::trading::{MonitorMode, SignatureSender, TradingVolume, VolumeTracker};
+use std::time::{Duration, Instant};
+use std::sync::Arc;
 use tokio::sync::Mutex as TokioMutex;
 use log::{info, warn, error};
+use rand::Rng;
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// How long the bot may go without any sign of life, an update or a
+/// successful `getMe`, before the dispatcher is torn down for a restart.
+const DISPATCHER_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// How often the heartbeat watchdog probes Telegram and checks the last
+/// sign of life.
+const HEARTBEAT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Telegram rejects message texts longer than this many characters.
+const TELEGRAM_MESSAGE_LIMIT: usize = 4096;
+const STREAM_RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const STREAM_RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(60);
 
 pub struct WhaleBot {
-   bot: Bot,
-   chat_id: i64,
-   volume_tracker: Arc<TokioMutex<VolumeTracker>>,
-   is_tracking: Arc<TokioMutex<bool>>,
+   state: AppState,
+   last_update: Arc<TokioMutex<Instant>>,
+   idle_timeout: Duration,
 }
 
 impl WhaleBot {
-   pub async fn new(token: &str, chat_id: i64) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-       info!("Initializing WhaleBot with chat_id: {}", chat_id);
+   pub async fn new(token: &str, chat_id: i64) -> Result<Self, BotError> {
+       let volume_tracker = VolumeTracker::new(
+           "https://api.mainnet-beta.solana.com",
+           5000.0,
+           10000.0
+       );
+       Self::with_tracker(token, AlertRoutes::new(vec![ChatId(chat_id)]), volume_tracker).await
+   }
+
+   pub async fn with_tracker(
+       token: &str,
+       alert_routes: AlertRoutes,
+       volume_tracker: VolumeTracker
+   ) -> Result<Self, BotError> {
+       info!("Initializing WhaleBot with alert routes: {:?}", alert_routes);
        let bot = Bot::new(token);
 
        let mut retry_count = 0;
@@ -31,10 +63,14 @@ impl WhaleBot {
        loop {
            match bot.get_me().await {
                Ok(_) => break,
+               Err(e) if is_permanent_telegram_error(&e) => {
+                   return Err(e.into());
+               }
                Err(e) => {
                    retry_count += 1;
                    if retry_count > max_retries {
-                       return Err(format!("Failed to initialize bot after {} retries: {}", max_retries, e).into());
+                       error!("Failed to initialize bot after {} retries", max_retries);
+                       return Err(e.into());
                    }
                    eprintln!("Bot initialization error: {}. Retrying...", e);
                    tokio::time::sleep(Duration::from_secs(5)).await;
@@ -42,26 +78,170 @@ impl WhaleBot {
            }
        }
 
-       let volume_tracker = VolumeTracker::new(
-           "https://api.mainnet-beta.solana.com",
-           5000.0,
-           10000.0
-       );
-
        Ok(Self {
-           bot,
-           chat_id,
-           volume_tracker: Arc::new(TokioMutex::new(volume_tracker)),
-           is_tracking: Arc::new(TokioMutex::new(false)),
+           state: AppState::new(bot, alert_routes, volume_tracker),
+           last_update: Arc::new(TokioMutex::new(Instant::now())),
+           idle_timeout: DISPATCHER_IDLE_TIMEOUT,
        })
    }
 
-   pub async fn start(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+   pub fn with_monitor_mode(mut self, monitor_mode: MonitorMode) -> Self {
+       self.state.monitor_mode = monitor_mode;
+       self
+   }
+
+   pub fn with_batch_alerts(mut self, batch_alerts: bool) -> Self {
+       self.state.batch_alerts = batch_alerts;
+       self
+   }
+
+   pub fn with_poll_jitter(mut self, poll_jitter: Duration) -> Self {
+       self.state.poll_jitter = poll_jitter;
+       self
+   }
+
+   pub fn with_stream_channel_capacity(mut self, capacity: usize) -> Self {
+       self.state.stream_channel_capacity = capacity;
+       self
+   }
+
+   pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+       self.idle_timeout = idle_timeout;
+       self
+   }
+
+   /// Splits `text` into chunks of at most `limit` characters, preferring line
+   /// boundaries so alerts are not cut mid-line.
+   fn split_message(text: &str, limit: usize) -> Vec<String> {
+       let mut chunks = Vec::new();
+       let mut current = String::new();
+
+       for line in text.lines() {
+           let line_len = line.chars().count();
+           let current_len = current.chars().count();
+           if current_len > 0 && current_len + 1 + line_len > limit {
+               chunks.push(std::mem::take(&mut current));
+           }
+
+           if line_len > limit {
+               let chars: Vec<char> = line.chars().collect();
+               for piece in chars.chunks(limit) {
+                   chunks.push(piece.iter().collect());
+               }
+               continue;
+           }
+
+           if !current.is_empty() {
+               current.push('\n');
+           }
+           current.push_str(line);
+       }
+
+       if !current.is_empty() || chunks.is_empty() {
+           chunks.push(current);
+       }
+       chunks
+   }
+
+   async fn send_long_message(bot: &Bot, chat_id: ChatId, text: &str) -> Result<(), RequestError> {
+       for chunk in Self::split_message(text, TELEGRAM_MESSAGE_LIMIT) {
+           bot.send_message(chat_id, chunk).await?;
+       }
+       Ok(())
+   }
+
+   /// Offsets `interval` by a uniform random amount in `[-jitter, +jitter]`,
+   /// never going below zero.
+   fn jittered(interval: Duration, jitter: Duration) -> Duration {
+       if jitter.is_zero() {
+           return interval;
+       }
+       let offset = rand::thread_rng().gen_range(-jitter.as_secs_f64()..=jitter.as_secs_f64());
+       Duration::from_secs_f64((interval.as_secs_f64() + offset).max(0.0))
+   }
+
+   /// Sends an alert to the recipients `AlertRoutes::recipients` picks for
+   /// `kind`. Succeeds if at least one chat received it; otherwise returns the
+   /// last delivery error.
+   async fn send_alert(state: &AppState, kind: AlertKind, origin: ChatId, text: &str) -> Result<(), RequestError> {
+       let targets = state.alert_routes.recipients(kind, origin);
+
+       let mut last_error = None;
+       let mut delivered = false;
+       for chat_id in targets {
+           match Self::send_long_message(&state.bot, chat_id, text).await {
+               Ok(()) => delivered = true,
+               Err(e) => {
+                   error!("Error sending {:?} alert to chat {}: {}", kind, chat_id, e);
+                   last_error = Some(e);
+               }
+           }
+       }
+
+       match last_error {
+           Some(e) if !delivered => Err(e),
+           _ => Ok(()),
+       }
+   }
+
+   /// The texts to send for one cycle's alerts of a kind: one per alert or,
+   /// with `batch`, all of them joined into one (still split at the Telegram
+   /// length limit when sent).
+   fn alert_texts(messages: Vec<String>, batch: bool) -> Vec<String> {
+       if batch && !messages.is_empty() {
+           vec![messages.join("\n\n")]
+       } else {
+           messages
+       }
+   }
+
+   /// Sends one cycle's alerts of a kind as `alert_texts` groups them. Stops
+   /// early only on a permanent error.
+   async fn send_alerts(state: &AppState, kind: AlertKind, origin: ChatId, messages: Vec<String>) -> Result<(), RequestError> {
+       for message in Self::alert_texts(messages, state.batch_alerts) {
+           match Self::send_alert(state, kind, origin, &message).await {
+               Err(e) if is_permanent_telegram_error(&e) => return Err(e),
+               _ => {}
+           }
+       }
+       Ok(())
+   }
+
+   fn is_stale(last_update: Instant, idle_timeout: Duration) -> bool {
+       last_update.elapsed() >= idle_timeout
+   }
+
+   /// Returns once `probe` has failed and no update arrived for
+   /// `idle_timeout`. A quiet chat is not a reason to restart: every
+   /// successful probe counts as a sign of life too.
+   async fn watch_heartbeat<F, Fut>(probe: F, last_update: Arc<TokioMutex<Instant>>, idle_timeout: Duration) -> Duration
+   where
+       F: Fn() -> Fut,
+       Fut: std::future::Future<Output = bool>,
+   {
+       loop {
+           tokio::time::sleep(std::cmp::min(HEARTBEAT_CHECK_INTERVAL, idle_timeout)).await;
+           if probe().await {
+               *last_update.lock().await = Instant::now();
+               continue;
+           }
+           let last = *last_update.lock().await;
+           if Self::is_stale(last, idle_timeout) {
+               return last.elapsed();
+           }
+       }
+   }
+
+   pub async fn start(&self) -> Result<(), BotError> {
        let mut retry_interval = Duration::from_secs(5);
 
        loop {
            match self.setup_handlers().await {
                Ok(_) => break,
+               Err(e) if e.is_fatal() => {
+                   error!("Permanent Telegram error, not retrying: {}", e);
+                   return Err(e);
+               }
                Err(e) => {
                    eprintln!("Bot setup error: {}. Retrying in {:?}...", e, retry_interval);
                    tokio::time::sleep(retry_interval).await;
@@ -76,219 +256,56 @@ impl WhaleBot {
        Ok(())
    }
 
-   async fn setup_handlers(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+   async fn setup_handlers(&self) -> Result<(), BotError> {
        info!("Setting up WhaleBot command handlers");
-       let bot = self.bot.clone();
+       let bot = self.state.bot.clone();
        bot.delete_webhook().send().await?;
 
-       let volume_tracker = Arc::clone(&self.volume_tracker);
-       let is_tracking = Arc::clone(&self.is_tracking);
+       let last_update = Arc::clone(&self.last_update);
+       *last_update.lock().await = Instant::now();
 
        let command_handler = Update::filter_message()
            .filter_command::<Command>()
-           .endpoint({
-               let volume_tracker = Arc::clone(&volume_tracker);
-               let is_tracking = Arc::clone(&is_tracking);
-               move |bot: Bot, msg: Message, cmd: Command| {
-                   error!("Command received: {:?}, raw text: {}", cmd, msg.text().unwrap_or_default());
-                   let volume_tracker = Arc::clone(&volume_tracker);
-                   let is_tracking = Arc::clone(&is_tracking);
-                   async move {
-                       info!("Received command: {:?} from chat_id: {}", cmd, msg.chat.id);
-                       match cmd {
-                           Command::Start => {
-                               let has_monitored_tokens = {
-                                   let tracker = volume_tracker.lock().await;
-                                   !tracker.monitored_tokens.is_empty()
-                               };
-
-                               if !has_monitored_tokens {
-                                   info!("Attempt to start monitoring without configured tokens");
-                                   bot.send_message(
-                                       ChatId(msg.chat.id.0),
-                                       "⚠️ Please use /monitorToken <symbol> first to select which token to monitor (e.g., /monitorToken SOL)"
-                                   ).await?;
-                                   return Ok(());
-                               }
+           .endpoint(Self::handle_command);
 
-                               info!("Starting monitoring for chat_id: {}", msg.chat.id);
-                               *is_tracking.lock().await = true;
-
-                               let monitor_bot = bot.clone();
-                               let monitor_tracker = Arc::clone(&volume_tracker);
-                               let monitor_is_tracking = Arc::clone(&is_tracking);
-                               let chat_id = msg.chat.id;
-
-                               tokio::spawn(async move {
-                                   info!("Spawned monitoring task for chat_id: {}", chat_id);
-                                   while *monitor_is_tracking.lock().await {
-                                       info!("Starting trade tracking cycle");
-                                       let hot_pairs = {
-                                           let mut tracker = monitor_tracker.lock().await;
-                                           match tracker.track_trades().await {
-                                               Ok(pairs) => {
-                                                   info!("Successfully tracked trades, found {} hot pairs", pairs.len());
-                                                   pairs
-                                               }
-                                               Err(e) => {
-                                                   error!("Error tracking trades: {}", e);
-                                                   Vec::new()
-                                               }
-                                           }
-                                       };
-
-                                       for volume in hot_pairs {
-                                           if volume.trade_count >= 3 {
-                                               info!("Hot trading activity detected for token: {}", volume.token_name);
-                                               let message = format!(
-                                                   "🔥 Hot Trading Activity Detected!\n\
-                                                   Token: {}\n\
-                                                   Average Trade: ${:.2}\n\
-                                                   Spot Trades: {}\n\
-                                                   AMM Swaps: {}\n\
-                                                   Total Trades: {}\n\
-                                                   Total Volume: ${:.2}",
-                                                   volume.token_name,
-                                                   volume.average_trade_size,
-                                                   volume.trade_count,
-                                                   volume.swap_count,
-                                                   volume.trade_count + volume.swap_count,
-                                                   volume.total_volume
-                                               );
-
-                                               if let Err(e) = monitor_bot.send_message(ChatId(chat_id.0), message).await {
-                                                   error!("Error sending message: {}", e);
-                                               }
-                                           }
-                                       }
-                                       info!("Sleeping for 30 seconds before next cycle");
-                                       tokio::time::sleep(Duration::from_secs(30)).await;
-                                   }
-                                   info!("Monitoring task ended for chat_id: {}", chat_id);
-                               });
-
-                               let monitored_tokens = {
-                                   let tracker = volume_tracker.lock().await;
-                                   tracker.get_monitored_tokens_list()
-                               };
-
-                               info!("Sending start confirmation message");
-                               bot.send_message(
-                                   ChatId(msg.chat.id.0),
-                                   format!("🔍 Started monitoring trading patterns for: {}", monitored_tokens)
-                               ).await?;
-                           },
-                           Command::Stop => {
-                               info!("Stopping monitoring for chat_id: {}", msg.chat.id);
-                               *is_tracking.lock().await = false;
-                               bot.send_message(
-                                   ChatId(msg.chat.id.0),
-                                   "⏹️ Monitoring stopped. Use /start to resume monitoring."
-                               ).await?;
-                           },
-                           Command::MonitorToken(token_symbol) => {
-                               info!("Adding token {} to monitoring list", token_symbol);
-                               let mut tracker = volume_tracker.lock().await;
-                               match tracker.add_monitored_token(&token_symbol).await {
-                                   Ok(token_info) => {
-                                       info!("Successfully added token {}", token_info.symbol);
-                                       bot.send_message(
-                                           ChatId(msg.chat.id.0),
-                                           format!("🎯 Now monitoring {} token", token_info.symbol)
-                                       ).await?;
-                                   }
-                                   Err(e) => {
-                                       bot.send_message(
-                                           ChatId(msg.chat.id.0),
-                                           format!("❌ Error: {}", e)
-                                       ).await?;
-                                   }
-                               }
-                           },
-                           Command::MonitorTokenVolume(input) => {
-                               let parts: Vec<&str> = input.split_whitespace().collect();
-
-                               if parts.len() != 4 {
-                                   bot.send_message(
-                                       ChatId(msg.chat.id.0),
-                                       "❌ Invalid format! Use: /monitortokenvolume <token> <min> <max> <timeframe>",
-                                   ).await?;
-                                   return Ok(());
-                               }
+       // Known commands whose arguments fail to parse never reach
+       // handle_command, so the user is told what was wrong instead.
+       let invalid_command_handler = Update::filter_message()
+           .endpoint(Self::handle_invalid_command);
 
-                               let token_symbol = parts[0].to_string();
-                               let min: f64 = match parts[1].parse() {
-                                   Ok(val) => val,
-                                   Err(_) => {
-                                       bot.send_message(ChatId(msg.chat.id.0), "❌ Invalid min volume format!").await?;
-                                       return Ok(());
-                                   }
-                               };
-
-                               let max: f64 = match parts[2].parse() {
-                                   Ok(val) => val,
-                                   Err(_) => {
-                                       bot.send_message(ChatId(msg.chat.id.0), "❌ Invalid max volume format!").await?;
-                                       return Ok(());
-                                   }
-                               };
-
-                               let timeframe: u64 = match parts[3].parse() {
-                                   Ok(val) => val,
-                                   Err(_) => {
-                                       bot.send_message(ChatId(msg.chat.id.0), "❌ Invalid timeframe format!").await?;
-                                       return Ok(());
-                                   }
-                               };
-
-                               info!(
-                                   "Updating volume thresholds for {}: min=${}, max=${}, timeframe={}min",
-                                   token_symbol, min, max, timeframe
-                               );
-
-                               let mut tracker = volume_tracker.lock().await;
-
-                               match tracker.get_token_info(&token_symbol).await {
-                                   Ok(token_info) => {
-                                       if !tracker.monitored_tokens.contains(&token_info.address) {
-                                           bot.send_message(
-                                               ChatId(msg.chat.id.0),
-                                               format!(
-                                                   "❌ Please first add {} to monitoring using /monitorToken",
-                                                   token_symbol
-                                               ),
-                                           ).await?;
-                                           return Ok(());
-                                       }
-
-                                       tracker.set_token_volume_threshold(token_info.address, min, max, timeframe);
-                                       bot.send_message(
-                                           ChatId(msg.chat.id.0),
-                                           format!(
-                                               "📊 Updated monitoring thresholds for {}:\nMin Volume: ${}\nMax Volume: ${}\nTimeframe: {} minutes",
-                                               token_symbol, min, max, timeframe
-                                           ),
-                                       ).await?;
-                                   }
-                                   Err(_) => {
-                                       bot.send_message(
-                                           ChatId(msg.chat.id.0),
-                                           format!("❌ Error: Token {} not found", token_symbol),
-                                       ).await?;
-                                   }
-                               }
-                           },
-                           _ => {
-                               warn!("Unhandled command received: {:?}", cmd);
-                           }
+       let heartbeat = {
+           let last_update = Arc::clone(&last_update);
+           move || {
+               let last_update = Arc::clone(&last_update);
+               async move {
+                   *last_update.lock().await = Instant::now();
+               }
+           }
+       };
+
+       let handler = dptree::entry()
+           .inspect_async(heartbeat)
+           .branch(command_handler)
+           .branch(invalid_command_handler);
+
+       let probe = {
+           let bot = bot.clone();
+           move || {
+               let bot = bot.clone();
+               async move {
+                   match bot.get_me().await {
+                       Ok(_) => true,
+                       Err(e) => {
+                           warn!("Heartbeat getMe failed: {}", e);
+                           false
                        }
-                       Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
                    }
                }
-           });
+           }
+       };
 
-       let mut dispatcher = Dispatcher::builder(bot, dptree::entry().branch(command_handler))
-           .dependencies(dptree::deps![volume_tracker, is_tracking])
+       let mut dispatcher = Dispatcher::builder(bot, handler)
+           .dependencies(dptree::deps![self.state.clone()])
            .error_handler(LoggingErrorHandler::with_custom_text("Error in command handler"))
            .enable_ctrlc_handler()
            .build();
@@ -297,12 +314,848 @@ impl WhaleBot {
            _ = dispatcher.dispatch() => {
                info!("Dispatcher finished");
            }
-           _ = tokio::time::sleep(Duration::from_secs(3600)) => {
-               error!("Dispatcher timeout, restarting...");
-               return Err("Dispatcher timeout".into());
+           idle = Self::watch_heartbeat(probe, last_update, self.idle_timeout) => {
+               error!("Telegram unreachable for {:?}, restarting dispatcher...", idle);
+               return Err(BotError::Dispatcher(format!("Telegram unreachable for {:?}", idle)));
            }
        }
 
        Ok(())
    }
-}
\ No newline at end of file
+
+   async fn monitor_trades(state: AppState, chat_id: ChatId) {
+       info!("Spawned monitoring task for chat_id: {} in {:?} mode", chat_id, state.monitor_mode);
+       let (signature_sender, mut signature_receiver) = SignatureSender::channel(state.stream_channel_capacity);
+       if state.monitor_mode == MonitorMode::Stream {
+           let (ws_url, programs) = {
+               let tracker = state.volume_tracker.lock().await;
+               (tracker.websocket_url(), tracker.tracked_programs().to_vec())
+           };
+           for program_id in programs {
+               let sender = signature_sender.clone();
+               let ws_url = ws_url.clone();
+               spawn_supervised(format!("logs subscription {}", program_id), true, move || {
+                   let sender = sender.clone();
+                   let ws_url = ws_url.clone();
+                   async move {
+                       // Reconnects back off while the subscription keeps
+                       // failing and start over once it ran successfully.
+                       let mut backoff = STREAM_RECONNECT_INITIAL_BACKOFF;
+                       while !sender.is_closed() {
+                           match VolumeTracker::stream_program_signatures(&ws_url, program_id, sender.clone()).await {
+                               Ok(()) => backoff = STREAM_RECONNECT_INITIAL_BACKOFF,
+                               Err(e) => {
+                                   error!("Logs subscription for program {} failed, reconnecting in {:?}: {}", program_id, backoff, e);
+                               }
+                           }
+                           tokio::time::sleep(backoff).await;
+                           backoff = (backoff * 2).min(STREAM_RECONNECT_MAX_BACKOFF);
+                       }
+                   }
+               });
+           }
+       }
+       // The loop keeps a handle only to read the drop counter.
+       let drop_counter = signature_sender;
+
+       while state.continue_monitoring().await {
+           if *state.is_paused.lock().await {
+               // Streamed signatures seen while paused are dropped rather
+               // than replayed in one burst on resume.
+               while signature_receiver.try_recv().is_ok() {}
+               tokio::time::sleep(state.monitor_mode.interval()).await;
+               continue;
+           }
+
+           info!("Starting trade tracking cycle");
+           let (hot_pairs, large_trades, min_confidence) = {
+               let mut tracker = state.volume_tracker.lock().await;
+               let result = match state.monitor_mode {
+                   MonitorMode::Poll { .. } => tracker.track_trades().await,
+                   MonitorMode::Stream => {
+                       let mut signatures = Vec::new();
+                       while let Ok(signature) = signature_receiver.try_recv() {
+                           signatures.push(signature);
+                       }
+                       let dropped = drop_counter.take_dropped();
+                       if dropped > 0 {
+                           warn!("Dropped {} streamed signatures since the last cycle, stream channel full", dropped);
+                       }
+                       tracker.track_streamed_signatures(signatures).await
+                   }
+               };
+               let pairs = match result {
+                   Ok(pairs) => {
+                       info!("Successfully tracked trades, found {} hot pairs", pairs.len());
+                       pairs
+                   }
+                   Err(e) => {
+                       error!("Error tracking trades: {}", e);
+                       Vec::new()
+                   }
+               };
+               (pairs, tracker.take_large_trades(), tracker.min_alert_confidence)
+           };
+
+           let large_trade_messages: Vec<String> = large_trades
+               .iter()
+               .map(|trade| format!(
+                   "🐋 Large Trade Detected!\n\
+                   Token: {}\n\
+                   Value: ${:.2}\n\
+                   Transaction: {}",
+                   trade.token_name,
+                   trade.value,
+                   trade.signature
+               ))
+               .collect();
+
+           let hot_pair_messages = Self::hot_pair_messages(hot_pairs, min_confidence);
+
+           for (kind, messages) in [
+               (AlertKind::LargeTrade, large_trade_messages),
+               (AlertKind::HotPair, hot_pair_messages),
+           ] {
+               if let Err(e) = Self::send_alerts(&state, kind, chat_id, messages).await {
+                   if is_permanent_telegram_error(&e) {
+                       error!("Cannot deliver alerts to any chat, stopping monitoring: {}", e);
+                       *state.is_tracking.lock().await = false;
+                       break;
+                   }
+               }
+           }
+           let interval = Self::jittered(state.monitor_mode.interval(), state.poll_jitter);
+           info!("Sleeping for {:?} before next cycle", interval);
+           tokio::time::sleep(interval).await;
+       }
+       info!("Monitoring task ended for chat_id: {}", chat_id);
+   }
+
+   /// Alert texts for this cycle's hot pairs, leaving out any whose
+   /// confidence is below `min_confidence`.
+   pub(crate) fn hot_pair_messages(hot_pairs: Vec<TradingVolume>, min_confidence: f64) -> Vec<String> {
+       let mut hot_pair_messages = Vec::new();
+       for volume in hot_pairs {
+           if volume.trade_count + volume.swap_count >= 3 {
+               let confidence = volume.confidence();
+               if confidence < min_confidence {
+                   info!("Suppressing alert for {}: confidence {:.2} below {:.2}",
+                       volume.token_name, confidence, min_confidence);
+                   continue;
+               }
+               info!("Hot trading activity detected for token: {}", volume.token_name);
+               let message = format!(
+                   "🔥 Hot Trading Activity Detected!\n\
+                   Token: {}\n\
+                   Average Trade: ${:.2}\n\
+                   Spot Trades: {}\n\
+                   AMM Swaps: {}\n\
+                   Total Trades: {}\n\
+                   Total Volume: ${:.2}\n\
+                   Confidence: {:.0}%",
+                   volume.token_name,
+                   volume.average_trade_size,
+                   volume.trade_count,
+                   volume.swap_count,
+                   volume.trade_count + volume.swap_count,
+                   volume.total_volume,
+                   confidence * 100.0
+               );
+               hot_pair_messages.push(message);
+           }
+       }
+       hot_pair_messages
+   }
+
+   fn diagnostic_line(service: &str, started: Instant, result: Result<String, String>) -> String {
+       let latency = started.elapsed().as_millis();
+       match result {
+           Ok(detail) => format!("✅ {}: {} ({} ms)", service, detail, latency),
+           Err(e) => format!("❌ {}: {} ({} ms)", service, e, latency),
+       }
+   }
+
+   /// Checks the RPC node is healthy and reports its slot.
+   async fn probe_rpc(rpc_client: Arc<RpcClient>) -> Result<String, String> {
+       tokio::task::spawn_blocking(move || {
+           rpc_client.get_health().map_err(|e| e.to_string())?;
+           rpc_client.get_slot()
+               .map(|slot| format!("healthy at slot {}", slot))
+               .map_err(|e| e.to_string())
+       })
+       .await
+       .unwrap_or_else(|e| Err(e.to_string()))
+   }
+
+   /// Probes each external dependency independently so one failure does not
+   /// hide the state of the others. The RPC probe and the SOL price use the
+   /// handles kept in `AppState`, so a running tracking cycle cannot stall
+   /// this; the price is served from the cache within its TTL.
+   async fn run_diagnostics(bot: &Bot, state: &AppState) -> String {
+       let started = Instant::now();
+       let rpc = Self::probe_rpc(Arc::clone(&state.rpc_client)).await;
+       let rpc_line = Self::diagnostic_line("Solana RPC", started, rpc);
+
+       let started = Instant::now();
+       let price = state.price_cache
+           .get_sol_usd_price()
+           .await
+           .map(|price| format!("SOL ${:.2}", price))
+           .map_err(|e| e.to_string());
+       let price_line = Self::diagnostic_line("Raydium price API", started, price);
+
+       let started = Instant::now();
+       let telegram = bot.get_me()
+           .await
+           .map(|me| format!("connected as @{}", me.username()))
+           .map_err(|e| e.to_string());
+       let telegram_line = Self::diagnostic_line("Telegram", started, telegram);
+
+       format!("🩺 Diagnostics\n{}\n{}\n{}", rpc_line, price_line, telegram_line)
+   }
+
+   /// The reply for a known command whose arguments failed to parse. Plain
+   /// text, unknown commands and commands for another bot get none.
+   fn invalid_command_reply(text: &str, bot_name: &str) -> Option<String> {
+       if !text.starts_with('/') {
+           return None;
+       }
+
+       match Command::parse(text, bot_name) {
+           Err(ParseError::UnknownCommand(_)) | Err(ParseError::WrongBotName(_)) | Ok(_) => None,
+           Err(e) => {
+               warn!("Rejected command {:?}: {}", text, e);
+               Some(format!("❌ Invalid arguments: {}", e))
+           }
+       }
+   }
+
+   async fn handle_invalid_command(bot: Bot, msg: Message, me: Me) -> Result<(), BotError> {
+       let Some(reply) = msg.text().and_then(|text| Self::invalid_command_reply(text, me.username())) else {
+           return Ok(());
+       };
+       bot.send_message(ChatId(msg.chat.id.0), reply).await?;
+       Ok(())
+   }
+
+   /// The /version reply, with the commit when the build recorded one.
+   fn version_text(commit: Option<&str>) -> String {
+       match commit {
+           Some(commit) => format!("🏷️ Version {} (commit {})", env!("CARGO_PKG_VERSION"), commit),
+           None => format!("🏷️ Version {}", env!("CARGO_PKG_VERSION")),
+       }
+   }
+
+   async fn handle_command(bot: Bot, msg: Message, cmd: Command, state: AppState) -> Result<(), BotError> {
+       info!("Received command: {:?} from chat_id: {}", cmd, msg.chat.id);
+       match cmd {
+           Command::Start => {
+               let has_monitored_tokens = {
+                   let tracker = state.volume_tracker.lock().await;
+                   !tracker.monitored_tokens.is_empty()
+               };
+
+               if !has_monitored_tokens {
+                   info!("Attempt to start monitoring without configured tokens");
+                   bot.send_message(
+                       ChatId(msg.chat.id.0),
+                       "⚠️ Please use /monitorToken <symbol or mint> first to select which token to monitor (e.g., /monitorToken SOL)"
+                   ).await?;
+                   return Ok(());
+               }
+
+               info!("Starting monitoring for chat_id: {}", msg.chat.id);
+               if state.begin_monitoring().await {
+                   // A restarted monitor exits on its own if /stop was issued.
+                   let monitor_state = state.clone();
+                   let chat_id = msg.chat.id;
+                   spawn_supervised(format!("monitor for chat {}", chat_id), true, move || {
+                       Self::monitor_trades(monitor_state.clone(), chat_id)
+                   });
+               } else {
+                   info!("Monitoring task already running, not spawning another");
+               }
+
+               let monitored_tokens = {
+                   let tracker = state.volume_tracker.lock().await;
+                   tracker.get_monitored_tokens_list()
+               };
+
+               info!("Sending start confirmation message");
+               Self::send_long_message(
+                   &bot,
+                   msg.chat.id,
+                   &format!("🔍 Started monitoring trading patterns for: {}", monitored_tokens)
+               ).await?;
+           },
+           Command::Stop => {
+               info!("Stopping monitoring for chat_id: {}", msg.chat.id);
+               *state.is_tracking.lock().await = false;
+               *state.is_paused.lock().await = false;
+               bot.send_message(
+                   ChatId(msg.chat.id.0),
+                   "⏹️ Monitoring stopped. Use /start to resume monitoring."
+               ).await?;
+           },
+           Command::Diagnostics => {
+               info!("Running diagnostics for chat_id: {}", msg.chat.id);
+               let report = Self::run_diagnostics(&bot, &state).await;
+               bot.send_message(ChatId(msg.chat.id.0), report).await?;
+           },
+           Command::Version => {
+               bot.send_message(ChatId(msg.chat.id.0), Self::version_text(option_env!("GIT_COMMIT_HASH"))).await?;
+           },
+           Command::Pause => {
+               if !state.set_paused(true).await {
+                   bot.send_message(ChatId(msg.chat.id.0), "⚠️ Monitoring is not running. Use /start first.").await?;
+                   return Ok(());
+               }
+               info!("Paused monitoring for chat_id: {}", msg.chat.id);
+               bot.send_message(
+                   ChatId(msg.chat.id.0),
+                   "⏸️ Monitoring paused. Use /resume to continue with the same settings."
+               ).await?;
+           },
+           Command::Resume => {
+               if !state.set_paused(false).await {
+                   bot.send_message(ChatId(msg.chat.id.0), "⚠️ Monitoring is not running. Use /start first.").await?;
+                   return Ok(());
+               }
+               info!("Resumed monitoring for chat_id: {}", msg.chat.id);
+               bot.send_message(ChatId(msg.chat.id.0), "▶️ Monitoring resumed.").await?;
+           },
+           Command::MonitorToken(token_symbol) => {
+               info!("Adding token {} to monitoring list", token_symbol);
+               let mut tracker = state.volume_tracker.lock().await;
+               match tracker.add_monitored_token(&token_symbol).await {
+                   Ok(token_info) => {
+                       info!("Successfully added token {}", token_info.symbol);
+                       bot.send_message(
+                           ChatId(msg.chat.id.0),
+                           format!("🎯 Now monitoring {} token", token_info.symbol)
+                       ).await?;
+                   }
+                   Err(e) => {
+                       bot.send_message(
+                           ChatId(msg.chat.id.0),
+                           format!("❌ Error: {}", e)
+                       ).await?;
+                   }
+               }
+           },
+           Command::MonitorTokenVolume(token_symbol, min, max, timeframe) => {
+               info!(
+                   "Updating volume thresholds for {}: min=${}, max=${}, timeframe={}min",
+                   token_symbol, min, max, timeframe
+               );
+
+               let mut tracker = state.volume_tracker.lock().await;
+
+               match tracker.resolve_token(&token_symbol).await {
+                   Ok(token_info) => {
+                       if !tracker.monitored_tokens.contains(&token_info.address) {
+                           bot.send_message(
+                               ChatId(msg.chat.id.0),
+                               format!(
+                                   "❌ Please first add {} to monitoring using /monitorToken",
+                                   token_symbol
+                               ),
+                           ).await?;
+                           return Ok(());
+                       }
+
+                       if let Err(e) = tracker.set_token_volume_threshold(token_info.address, min, max, timeframe) {
+                           bot.send_message(ChatId(msg.chat.id.0), format!("❌ Error: {}", e)).await?;
+                           return Ok(());
+                       }
+                       bot.send_message(
+                           ChatId(msg.chat.id.0),
+                           format!(
+                               "📊 Updated monitoring thresholds for {}:\nMin Volume: ${}\nMax Volume: ${}\nTimeframe: {} minutes",
+                               token_symbol, min, max, timeframe
+                           ),
+                       ).await?;
+                   }
+                   Err(e) => {
+                       bot.send_message(
+                           ChatId(msg.chat.id.0),
+                           format!("❌ Error: {}", e),
+                       ).await?;
+                   }
+               }
+           },
+           Command::SetDefaultVolume(input) => {
+               let parts: Vec<&str> = input.split_whitespace().collect();
+               let parsed = match parts.as_slice() {
+                   [min, max, timeframe] => match (min.parse::<f64>(), max.parse::<f64>(), timeframe.parse::<u64>()) {
+                       (Ok(min), Ok(max), Ok(timeframe)) if min <= max => Some((min, max, timeframe)),
+                       _ => None,
+                   },
+                   _ => None,
+               };
+
+               let Some((min, max, timeframe)) = parsed else {
+                   bot.send_message(
+                       ChatId(msg.chat.id.0),
+                       "❌ Invalid format! Use: /setdefaultvolume <min> <max> <timeframe>",
+                   ).await?;
+                   return Ok(());
+               };
+
+               let result = state.volume_tracker.lock().await.set_default_volume(min, max, timeframe);
+               if let Err(e) = result {
+                   bot.send_message(ChatId(msg.chat.id.0), format!("❌ Error: {}", e)).await?;
+                   return Ok(());
+               }
+               bot.send_message(
+                   ChatId(msg.chat.id.0),
+                   format!(
+                       "📊 Default thresholds for newly added tokens:\nMin Volume: ${}\nMax Volume: ${}\nTimeframe: {} minutes",
+                       min, max, timeframe
+                   ),
+               ).await?;
+           },
+           Command::SetSingleTxAlert(input) => {
+               let parts: Vec<&str> = input.split_whitespace().collect();
+               let parsed = match parts.as_slice() {
+                   [token, "off"] => Some((token.to_string(), None)),
+                   [token, threshold] => match threshold.parse::<f64>() {
+                       Ok(threshold) if threshold > 0.0 => Some((token.to_string(), Some(threshold))),
+                       _ => None,
+                   },
+                   _ => None,
+               };
+
+               let Some((token_symbol, threshold)) = parsed else {
+                   bot.send_message(
+                       ChatId(msg.chat.id.0),
+                       "❌ Invalid format! Use: /setsingletxalert <token> <usd|off>",
+                   ).await?;
+                   return Ok(());
+               };
+
+               let mut tracker = state.volume_tracker.lock().await;
+               match tracker.resolve_token(&token_symbol).await {
+                   Ok(token_info) => {
+                       if !tracker.monitored_tokens.contains(&token_info.address) {
+                           bot.send_message(
+                               ChatId(msg.chat.id.0),
+                               format!(
+                                   "❌ Please first add {} to monitoring using /monitorToken",
+                                   token_symbol
+                               ),
+                           ).await?;
+                           return Ok(());
+                       }
+
+                       tracker.set_single_tx_alert_threshold(token_info.address, threshold);
+                       let reply = match threshold {
+                           Some(threshold) => format!("🐋 Alerting on any single {} trade above ${}", token_symbol, threshold),
+                           None => format!("🐋 Single-trade alerts disabled for {}", token_symbol),
+                       };
+                       bot.send_message(ChatId(msg.chat.id.0), reply).await?;
+                   }
+                   Err(e) => {
+                       bot.send_message(
+                           ChatId(msg.chat.id.0),
+                           format!("❌ Error: {}", e),
+                       ).await?;
+                   }
+               }
+           },
+           Command::ResetVolume(input) => {
+               let token_symbol = input.trim();
+               let mut tracker = state.volume_tracker.lock().await;
+               let cleared = if token_symbol.is_empty() {
+                   tracker.reset_volume(None)
+               } else {
+                   match tracker.resolve_token(token_symbol).await {
+                       Ok(token_info) => tracker.reset_volume(Some(&token_info.address)),
+                       Err(e) => {
+                           bot.send_message(ChatId(msg.chat.id.0), format!("❌ Error: {}", e)).await?;
+                           return Ok(());
+                       }
+                   }
+               };
+               bot.send_message(
+                   ChatId(msg.chat.id.0),
+                   format!("🧹 Cleared volume data for {} token(s)", cleared)
+               ).await?;
+           },
+           Command::SetCommitment(input) => {
+               let result = state.volume_tracker.lock().await.set_commitment(&input);
+               match result {
+                   Ok(()) => {
+                       bot.send_message(
+                           ChatId(msg.chat.id.0),
+                           format!("🔗 RPC commitment set to {} from the next cycle", input.trim().to_lowercase())
+                       ).await?;
+                   }
+                   Err(e) => {
+                       bot.send_message(ChatId(msg.chat.id.0), format!("❌ Error: {}", e)).await?;
+                   }
+               }
+           },
+           Command::SetMinConfidence(input) => {
+               let min_confidence: f64 = match input.trim().parse() {
+                   Ok(val) => val,
+                   Err(_) => {
+                       bot.send_message(ChatId(msg.chat.id.0), "❌ Invalid format! Use: /setminconfidence <0.0-1.0>").await?;
+                       return Ok(());
+                   }
+               };
+
+               let result = state.volume_tracker.lock().await.set_min_alert_confidence(min_confidence);
+               match result {
+                   Ok(()) => {
+                       bot.send_message(
+                           ChatId(msg.chat.id.0),
+                           format!("🎚️ Alerts below {:.0}% confidence will be suppressed", min_confidence * 100.0)
+                       ).await?;
+                   }
+                   Err(e) => {
+                       bot.send_message(ChatId(msg.chat.id.0), format!("❌ Error: {}", e)).await?;
+                   }
+               }
+           },
+           Command::TopMovers(input) => {
+               let limit: usize = if input.trim().is_empty() {
+                   5
+               } else {
+                   match input.trim().parse() {
+                       Ok(val) => val,
+                       Err(_) => {
+                           bot.send_message(ChatId(msg.chat.id.0), "❌ Invalid format! Use: /topmovers [count]").await?;
+                           return Ok(());
+                       }
+                   }
+               };
+
+               let movers = state.volume_tracker.lock().await.top_movers(limit);
+               if movers.is_empty() {
+                   bot.send_message(
+                       ChatId(msg.chat.id.0),
+                       "📈 Not enough history yet, top movers need at least two tracking cycles"
+                   ).await?;
+                   return Ok(());
+               }
+
+               let lines: Vec<String> = movers
+                   .iter()
+                   .enumerate()
+                   .map(|(i, mover)| {
+                       let change = if mover.change_pct.is_finite() {
+                           format!("{:+.1}%", mover.change_pct)
+                       } else {
+                           "new".to_string()
+                       };
+                       format!(
+                           "{}. {} {} (${:.2} → ${:.2})",
+                           i + 1, mover.token_name, change, mover.previous_volume, mover.current_volume
+                       )
+                   })
+                   .collect();
+               Self::send_long_message(
+                   &bot,
+                   msg.chat.id,
+                   &format!("📈 Top Movers\n{}", lines.join("\n"))
+               ).await?;
+           },
+           Command::Blacklist(mint) => {
+               let mint = mint.trim();
+               if Pubkey::from_str(mint).is_err() {
+                   bot.send_message(ChatId(msg.chat.id.0), "❌ Invalid format! Use: /blacklist <mint address>").await?;
+                   return Ok(());
+               }
+
+               state.volume_tracker.lock().await.blacklist_mint(mint);
+               bot.send_message(
+                   ChatId(msg.chat.id.0),
+                   format!("🚫 {} is blacklisted and will no longer be monitored or alerted on", mint)
+               ).await?;
+           },
+           _ => {
+               warn!("Unhandled command received: {:?}", cmd);
+           }
+       }
+       Ok(())
+   }
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+   use crate::bot::trading::tests::FakeRpc;
+   use crate::bot::trading::WSOL_MINT;
+   use serde_json::{json, Value};
+   use std::sync::Mutex;
+   use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+   /// Local stand-in for the Telegram Bot API. `sendMessage` echoes a message
+   /// back to the requested chat; `getMe` succeeds only when `healthy`.
+   /// Every request is recorded as its lowercased method and JSON body.
+   struct FakeBotApi {
+       url: reqwest::Url,
+       requests: Arc<Mutex<Vec<(String, Value)>>>,
+   }
+
+   impl FakeBotApi {
+       async fn start(healthy: bool) -> Self {
+           let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+           let url = reqwest::Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+           let requests = Arc::new(Mutex::new(Vec::new()));
+           let recorded = Arc::clone(&requests);
+           tokio::spawn(async move {
+               while let Ok((mut stream, _)) = listener.accept().await {
+                   let Some((method, body)) = Self::read_request(&mut stream).await else {
+                       continue;
+                   };
+                   recorded.lock().unwrap().push((method.clone(), body.clone()));
+                   let response = Self::respond(&method, &body, healthy);
+                   let response = format!(
+                       "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                       response.len(), response
+                   );
+                   let _ = stream.write_all(response.as_bytes()).await;
+               }
+           });
+           Self { url, requests }
+       }
+
+       fn bot(&self) -> Bot {
+           Bot::new("123:test").set_api_url(self.url.clone())
+       }
+
+       async fn read_request(stream: &mut tokio::net::TcpStream) -> Option<(String, Value)> {
+           let mut buffer = Vec::new();
+           let mut chunk = [0u8; 4096];
+           let header_end = loop {
+               let read = stream.read(&mut chunk).await.ok()?;
+               if read == 0 {
+                   return None;
+               }
+               buffer.extend_from_slice(&chunk[..read]);
+               if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                   break end + 4;
+               }
+           };
+           let headers = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+           let content_length = headers
+               .lines()
+               .find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(|len| len.trim().parse().unwrap_or(0)))
+               .unwrap_or(0);
+           while buffer.len() < header_end + content_length {
+               let read = stream.read(&mut chunk).await.ok()?;
+               if read == 0 {
+                   break;
+               }
+               buffer.extend_from_slice(&chunk[..read]);
+           }
+           let path = headers.split_whitespace().nth(1)?;
+           // Bot API method names are case-insensitive; teloxide capitalizes them.
+           let method = path.rsplit('/').next()?.to_lowercase();
+           let body = serde_json::from_slice(&buffer[header_end..]).unwrap_or(Value::Null);
+           Some((method, body))
+       }
+
+       fn respond(method: &str, body: &Value, healthy: bool) -> String {
+           let result = match method {
+               "getme" if healthy => json!({
+                   "id": 123, "is_bot": true, "first_name": "Whale", "username": "whale_bot",
+                   "can_join_groups": true, "can_read_all_group_messages": false, "supports_inline_queries": false,
+               }),
+               "sendmessage" => json!({
+                   "message_id": 1,
+                   "date": 0,
+                   "chat": { "id": body["chat_id"], "type": "private", "first_name": "Test" },
+                   "text": body["text"],
+               }),
+               _ => return json!({ "ok": false, "error_code": 500, "description": "Internal Server Error" }).to_string(),
+           };
+           json!({ "ok": true, "result": result }).to_string()
+       }
+
+       /// Chat ids of the `sendMessage` requests received, in order.
+       fn sent_to(&self) -> Vec<i64> {
+           self.requests
+               .lock()
+               .unwrap()
+               .iter()
+               .filter(|(method, _)| method == "sendmessage")
+               .filter_map(|(_, body)| body["chat_id"].as_i64())
+               .collect()
+       }
+   }
+
+   #[tokio::test(flavor = "multi_thread")]
+   async fn diagnostics_report_mixed_health() {
+       let api = FakeBotApi::start(false).await;
+       let tracker = VolumeTracker::with_rpc_client(FakeRpc::new("healthy").client(), 100.0, 10_000.0);
+       let state = AppState::new(api.bot(), AlertRoutes::new(vec![ChatId(1)]), tracker);
+       state.price_cache.insert(WSOL_MINT, 150.0);
+
+       let report = WhaleBot::run_diagnostics(&state.bot, &state).await;
+
+       let lines: Vec<&str> = report.lines().collect();
+       assert_eq!(lines[0], "🩺 Diagnostics");
+       assert!(lines[1].starts_with("✅ Solana RPC: healthy at slot 42"), "{}", report);
+       assert!(lines[2].starts_with("✅ Raydium price API: SOL $150.00"), "{}", report);
+       assert!(lines[3].starts_with("❌ Telegram: "), "{}", report);
+       assert_eq!(api.requests.lock().unwrap().iter().filter(|(method, _)| method == "getme").count(), 1);
+
+       let unhealthy = WhaleBot::probe_rpc(Arc::new(RpcClient::new_mock("fails".to_string()))).await;
+       assert!(WhaleBot::diagnostic_line("Solana RPC", Instant::now(), unhealthy).starts_with("❌ Solana RPC: "));
+   }
+
+   #[tokio::test]
+   async fn recently_active_bot_is_not_restarted() {
+       let idle_timeout = Duration::from_millis(50);
+       let last_update = Arc::new(TokioMutex::new(Instant::now()));
+       assert!(!WhaleBot::is_stale(Instant::now(), idle_timeout));
+
+       let healthy = WhaleBot::watch_heartbeat(|| async { true }, Arc::clone(&last_update), idle_timeout);
+       assert!(tokio::time::timeout(idle_timeout * 6, healthy).await.is_err());
+
+       let unreachable = WhaleBot::watch_heartbeat(|| async { false }, last_update, idle_timeout);
+       let idle = tokio::time::timeout(idle_timeout * 6, unreachable).await.unwrap();
+       assert!(idle >= idle_timeout);
+   }
+
+   #[test]
+   fn monitor_token_volume_parses_all_arguments() {
+       let command = Command::parse("/monitortokenvolume BONK 1000 5000 30", "whale_bot").unwrap();
+       assert!(matches!(
+           command,
+           Command::MonitorTokenVolume(token, min, max, timeframe)
+               if token == "BONK" && min == 1000.0 && max == 5000.0 && timeframe == 30
+       ));
+       assert_eq!(WhaleBot::invalid_command_reply("/monitortokenvolume BONK 1000 5000 30", "whale_bot"), None);
+   }
+
+   #[test]
+   fn malformed_arguments_get_an_invalid_command_reply() {
+       let reply = WhaleBot::invalid_command_reply("/monitortokenvolume BONK lots 5000 30", "whale_bot").unwrap();
+       assert!(reply.starts_with("❌ Invalid arguments: "));
+       let reply = WhaleBot::invalid_command_reply("/monitortokenvolume BONK 1000", "whale_bot").unwrap();
+       assert!(reply.starts_with("❌ Invalid arguments: "));
+
+       assert_eq!(WhaleBot::invalid_command_reply("hello", "whale_bot"), None);
+       assert_eq!(WhaleBot::invalid_command_reply("/nosuchcommand", "whale_bot"), None);
+       assert_eq!(WhaleBot::invalid_command_reply("/monitortokenvolume@other_bot BONK x", "whale_bot"), None);
+   }
+
+   #[test]
+   fn long_messages_split_at_line_boundaries() {
+       let text = format!("{}\n{}\n{}", "a".repeat(6), "b".repeat(3), "c".repeat(4));
+       assert_eq!(WhaleBot::split_message(&text, 10), vec!["aaaaaa\nbbb", "cccc"]);
+       assert_eq!(WhaleBot::split_message("", 10), vec![""]);
+
+       let long_line = "x".repeat(25);
+       assert_eq!(WhaleBot::split_message(&long_line, 10), vec!["x".repeat(10), "x".repeat(10), "x".repeat(5)]);
+   }
+
+   #[test]
+   fn multibyte_text_is_split_by_characters() {
+       let text = "🐋".repeat(7);
+       let chunks = WhaleBot::split_message(&text, 3);
+       assert_eq!(chunks, vec!["🐋🐋🐋", "🐋🐋🐋", "🐋"]);
+       assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 3));
+
+       let text = format!("ééé\n{}", "ü".repeat(4));
+       assert_eq!(WhaleBot::split_message(&text, 4), vec!["ééé", "üüüü"]);
+   }
+
+   fn volume(token_name: &str, swap_count: u32, average_trade_size: f64, largest_trade: f64) -> TradingVolume {
+       TradingVolume {
+           token_address: token_name.to_string(),
+           token_name: token_name.to_string(),
+           total_volume: average_trade_size * swap_count as f64,
+           trade_count: 0,
+           swap_count,
+           average_trade_size,
+           largest_trade,
+           last_update: std::time::SystemTime::now(),
+       }
+   }
+
+   #[test]
+   fn low_confidence_hot_pairs_are_suppressed() {
+       // Three swaps dominated by one large trade, against ten even swaps.
+       let shaky = volume("SHAKY", 3, 1_000.0, 2_800.0);
+       let steady = volume("STEADY", 10, 1_000.0, 1_000.0);
+       assert!(shaky.confidence() < 0.5 && steady.confidence() > 0.9);
+
+       let messages = WhaleBot::hot_pair_messages(vec![shaky.clone(), steady.clone()], 0.5);
+       assert_eq!(messages.len(), 1);
+       assert!(messages[0].contains("Token: STEADY"));
+       assert!(messages[0].contains("Confidence: 100%"));
+
+       assert_eq!(WhaleBot::hot_pair_messages(vec![shaky, steady], 0.0).len(), 2);
+   }
+
+   #[test]
+   fn jittered_interval_stays_within_the_jitter() {
+       let interval = Duration::from_secs(30);
+       let jitter = Duration::from_secs(5);
+       for _ in 0..1_000 {
+           let slept = WhaleBot::jittered(interval, jitter);
+           assert!(slept >= interval - jitter && slept <= interval + jitter, "{:?} outside ±{:?}", slept, jitter);
+       }
+
+       assert_eq!(WhaleBot::jittered(interval, Duration::ZERO), interval);
+       for _ in 0..100 {
+           assert!(WhaleBot::jittered(Duration::from_secs(1), jitter) <= Duration::from_secs(6));
+       }
+   }
+
+   #[test]
+   fn hot_tokens_are_batched_into_one_message() {
+       let hot_pairs = vec![
+           volume("BONK", 10, 1_000.0, 1_000.0),
+           volume("WIF", 10, 2_000.0, 2_000.0),
+           volume("JUP", 10, 3_000.0, 3_000.0),
+       ];
+       let messages = WhaleBot::hot_pair_messages(hot_pairs, 0.0);
+
+       let batched = WhaleBot::alert_texts(messages.clone(), true);
+       assert_eq!(batched.len(), 1);
+       for token in ["BONK", "WIF", "JUP"] {
+           assert!(batched[0].contains(&format!("Token: {}", token)));
+       }
+       assert_eq!(batched[0].matches("🔥 Hot Trading Activity Detected!").count(), 3);
+
+       assert_eq!(WhaleBot::alert_texts(messages, false).len(), 3);
+       assert!(WhaleBot::alert_texts(Vec::new(), true).is_empty());
+   }
+
+   #[test]
+   fn version_text_contains_the_package_version() {
+       let version = WhaleBot::version_text(None);
+       assert!(version.contains(env!("CARGO_PKG_VERSION")));
+       assert!(!version.contains("commit"));
+
+       let version = WhaleBot::version_text(Some("abc1234"));
+       assert!(version.contains(env!("CARGO_PKG_VERSION")));
+       assert!(version.ends_with("(commit abc1234)"));
+   }
+
+   #[tokio::test]
+   async fn hot_pair_alert_is_sent_to_every_routed_chat() {
+       let api = FakeBotApi::start(true).await;
+       let routes = AlertRoutes::new(vec![ChatId(1)]).with_route(AlertKind::HotPair, vec![ChatId(10), ChatId(-100123)]);
+       let tracker = VolumeTracker::new("http://127.0.0.1:1", 100.0, 10_000.0);
+       let state = AppState::new(api.bot(), routes, tracker);
+       let messages = WhaleBot::hot_pair_messages(vec![volume("BONK", 10, 1_000.0, 1_000.0)], 0.0);
+
+       WhaleBot::send_alerts(&state, AlertKind::HotPair, ChatId(99), messages).await.unwrap();
+
+       assert_eq!(api.sent_to(), vec![10, -100123]);
+       let requests = api.requests.lock().unwrap().clone();
+       assert!(requests.iter().all(|(_, body)| body["text"].as_str().unwrap().contains("Token: BONK")));
+
+       // Unrouted kinds reach the default chats and the chat that started monitoring.
+       api.requests.lock().unwrap().clear();
+       WhaleBot::send_alerts(&state, AlertKind::LargeTrade, ChatId(99), vec!["🐋 Large Trade Detected!".to_string()]).await.unwrap();
+       assert_eq!(api.sent_to(), vec![1, 99]);
+   }
+}
@@ -1,25 +1,85 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
 use std::sync::Arc;
+use crate::bot::error::BotError;
+use solana_account_decoder::parse_token::UiTokenAmount;
 use solana_client::rpc_client::RpcClient;
-use solana_client::rpc_config::RpcTransactionConfig;
-use solana_client::rpc_config::RpcSignaturesForAddressConfig;
-use solana_sdk::{commitment_config::CommitmentConfig, account::Account};
-use solana_rpc_client_api::config::GetConfirmedSignaturesForAddress2Config;
-use solana_transaction_status::{
-    option_serializer::OptionSerializer,
-    UiTransactionEncoding,
-    UiTransactionTokenBalance,
-};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::{RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_transaction_status::{UiTransactionEncoding, UiTransactionTokenBalance};
 use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
-use std::time::{SystemTime, Duration};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 use solana_program::pubkey::Pubkey;
 use std::str::FromStr;
-use log::{info, warn, error};
+use log::{debug, info, warn, error};
+use futures::StreamExt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
 
-const RAYDIUM_DEX_PROGRAM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
-const RAYDIUM_AMM_PROGRAM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+pub const RAYDIUM_AMM_PROGRAM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+pub const OPENBOOK_PROGRAM: &str = "srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX";
+pub const ORCA_WHIRLPOOL_PROGRAM: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+pub const JUPITER_AGGREGATOR_PROGRAM: &str = "JUP6LkbZbjS1jKKwapdHNy74zpZ9hY2mAj2Ey3CYsdZ";
+
+/// Every tracked program is scanned for up to `MAX_SIGNATURES_PER_PROGRAM`
+/// signatures per cycle, so only Raydium is tracked unless configured.
+const DEFAULT_TRACKED_PROGRAMS: [&str; 1] = [RAYDIUM_AMM_PROGRAM];
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+pub const USDC_MINT: &str = "EPjFWJd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+pub const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
+const DEFAULT_STABLE_MINTS: [&str; 2] = [USDC_MINT, USDT_MINT];
+const DEFAULT_PRICE_CACHE_TTL: Duration = Duration::from_secs(60);
+const METAPLEX_METADATA_PROGRAM: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+const MAX_SIGNATURES_PER_PROGRAM: usize = 1000;
+const VOLUME_HISTORY_CYCLES: usize = 10;
+const MAX_ALERTED_SIGNATURES: usize = 5000;
+const TOKEN_LIST_TTL: Duration = Duration::from_secs(3600);
+const RAYDIUM_TOKEN_LIST_URL: &str = "https://api-v3.raydium.io/mint/list";
+const RPC_FAILOVER_THRESHOLD: u32 = 3;
+const RPC_UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(60);
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+const STREAM_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How the monitoring loop discovers transactions for the tracked programs.
+/// Both modes feed the same balance and pricing analysis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MonitorMode {
+    /// Re-scan recent signatures over HTTP RPC every `interval`.
+    Poll { interval: Duration },
+    /// Collect signatures from `logsSubscribe` and analyze them in batches.
+    Stream,
+}
+
+impl MonitorMode {
+    pub fn interval(&self) -> Duration {
+        match self {
+            MonitorMode::Poll { interval } => *interval,
+            MonitorMode::Stream => STREAM_FLUSH_INTERVAL,
+        }
+    }
+}
+
+impl Default for MonitorMode {
+    fn default() -> Self {
+        MonitorMode::Poll { interval: DEFAULT_POLL_INTERVAL }
+    }
+}
+
+impl FromStr for MonitorMode {
+    type Err = String;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode.to_lowercase().as_str() {
+            "poll" => Ok(MonitorMode::default()),
+            "stream" => Ok(MonitorMode::Stream),
+            other => Err(format!("Unknown monitor mode '{}', expected 'poll' or 'stream'", other)),
+        }
+    }
+}
 
 #[derive(Debug, Deserialize)]
 struct RaydiumPriceResponse {
@@ -45,6 +105,49 @@ impl std::fmt::Display for TokenInfo {
     }
 }
 
+/// Floor a single balance change must clear before it is counted as a trade.
+///
+/// Precedence: a `Raw` minimum is checked first, against the change in the
+/// token's base units (lamports for SOL), so dust is dropped before any price
+/// lookup. A `Usd` minimum is checked after pricing. Either way the
+/// `min_volume`/`max_volume` band still applies afterwards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MinimumTransaction {
+    Raw(u64),
+    Usd(f64),
+}
+
+/// USD trade-size band and data window applied to one monitored token.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VolumeThreshold {
+    pub min_volume: f64,
+    pub max_volume: f64,
+    /// How long a cycle's volume stays in the token's history for
+    /// `top_movers` before it ages out.
+    pub time_window: Duration,
+}
+
+/// Change in a token's per-cycle volume between the last two tracking cycles.
+#[derive(Clone, Debug)]
+pub struct VolumeMover {
+    pub token_address: String,
+    pub token_name: String,
+    pub previous_volume: f64,
+    pub current_volume: f64,
+    /// Percentage change; infinite when the token had no volume last cycle.
+    pub change_pct: f64,
+}
+
+/// A single transaction whose value crossed its token's single-transaction
+/// alert threshold.
+#[derive(Clone, Debug)]
+pub struct LargeTrade {
+    pub token_address: String,
+    pub token_name: String,
+    pub signature: String,
+    pub value: f64,
+}
+
 #[derive(Clone)]
 pub struct TradingVolume {
     pub token_address: String,
@@ -53,61 +156,624 @@ pub struct TradingVolume {
     pub trade_count: u32,
     pub swap_count: u32,
     pub average_trade_size: f64,
+    pub largest_trade: f64,
     pub last_update: SystemTime,
 }
 
+impl TradingVolume {
+    /// Number of trades after which activity counts as fully established.
+    const CONFIDENT_TRADE_COUNT: f64 = 10.0;
+
+    /// Confidence proxy in [0, 1] for alert filtering. Half comes from how many
+    /// trades were seen, half from how evenly the volume is spread across them
+    /// (a single outsized trade dominating the total scores low).
+    pub fn confidence(&self) -> f64 {
+        let count = (self.trade_count + self.swap_count) as f64;
+        let activity = (count / Self::CONFIDENT_TRADE_COUNT).min(1.0);
+        let consistency = if self.largest_trade > 0.0 {
+            (self.average_trade_size / self.largest_trade).min(1.0)
+        } else {
+            0.0
+        };
+        0.5 * activity + 0.5 * consistency
+    }
+}
+
+/// Sending half of the bounded channel that carries streamed signatures to
+/// the monitoring loop. Sends never wait: a signature arriving while the
+/// channel is full is dropped and counted, so load shedding stays visible.
+#[derive(Clone)]
+pub struct SignatureSender {
+    sender: Sender<(Pubkey, String)>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl SignatureSender {
+    pub fn channel(capacity: usize) -> (Self, Receiver<(Pubkey, String)>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        (Self { sender, dropped: Arc::new(AtomicU64::new(0)) }, receiver)
+    }
+
+    /// Queues a signature, returning false once the receiver is gone.
+    pub fn send(&self, program_id: Pubkey, signature: String) -> bool {
+        match self.sender.try_send((program_id, signature)) {
+            Ok(()) => true,
+            Err(TrySendError::Full((_, signature))) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                debug!("Stream channel full, dropping signature {}", signature);
+                true
+            }
+            Err(TrySendError::Closed(_)) => false,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Signatures dropped because the channel was full, since the last call.
+    pub fn take_dropped(&self) -> u64 {
+        self.dropped.swap(0, Ordering::Relaxed)
+    }
+}
+
+/// USD prices by mint, reused for `ttl`. Clones share the cached prices, so
+/// `/diagnostics` can read them without waiting for the tracker lock.
+#[derive(Clone)]
+pub struct PriceCache {
+    prices: Arc<std::sync::Mutex<HashMap<String, (f64, SystemTime)>>>,
+    ttl: Duration,
+}
+
+impl PriceCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            prices: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            ttl,
+        }
+    }
+
+    fn cached(&self, mint: &str) -> Option<f64> {
+        let prices = self.prices.lock().unwrap_or_else(|e| e.into_inner());
+        prices
+            .get(mint)
+            .filter(|(_, fetched_at)| fetched_at.elapsed().is_ok_and(|age| age < self.ttl))
+            .map(|(price, _)| *price)
+    }
+
+    pub(crate) fn insert(&self, mint: &str, price: f64) {
+        self.prices
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(mint.to_string(), (price, SystemTime::now()));
+    }
+
+    pub async fn get(&self, mint: &str) -> Result<f64, BotError> {
+        if let Some(price) = self.cached(mint) {
+            return Ok(price);
+        }
+        let price = VolumeTracker::fetch_token_price(mint).await?;
+        self.insert(mint, price);
+        Ok(price)
+    }
+
+    /// USD price of SOL, served from the cache within its TTL.
+    pub async fn get_sol_usd_price(&self) -> Result<f64, BotError> {
+        self.get(WSOL_MINT).await
+    }
+}
+
+/// One token account's balance before and after a transaction, matched by
+/// `account_index`. An account the transaction created has no pre balance and
+/// one it closed has no post balance; the missing side counts as zero.
+struct BalanceChange<'a> {
+    mint: &'a str,
+    pre: Option<&'a UiTokenAmount>,
+    post: Option<&'a UiTokenAmount>,
+}
+
+impl BalanceChange<'_> {
+    fn amount(side: Option<&UiTokenAmount>) -> &str {
+        side.map_or("0", |amount| amount.amount.as_str())
+    }
+
+    fn is_changed(&self) -> bool {
+        Self::amount(self.pre) != Self::amount(self.post)
+    }
+
+    fn decimals(&self) -> u8 {
+        self.post.or(self.pre).map_or(0, |amount| amount.decimals)
+    }
+}
+
+/// One configured RPC endpoint and its recent health.
+struct RpcEndpoint {
+    client: Arc<RpcClient>,
+    consecutive_failures: u32,
+    unhealthy_until: Option<Instant>,
+}
+
+impl RpcEndpoint {
+    fn new(client: Arc<RpcClient>) -> Self {
+        Self {
+            client,
+            consecutive_failures: 0,
+            unhealthy_until: None,
+        }
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.unhealthy_until.is_none_or(|until| Instant::now() >= until)
+    }
+}
+
 pub struct VolumeTracker {
-    rpc_client: Arc<RpcClient>,
+    /// The primary endpoint first, then fallbacks in failover order.
+    rpc_endpoints: Vec<RpcEndpoint>,
+    active_endpoint: usize,
     pub min_volume: f64,
     pub max_volume: f64,
-    volume_data: HashMap<String, TradingVolume>,
     time_window: Duration,
+    token_thresholds: HashMap<String, VolumeThreshold>,
+    single_tx_alert_thresholds: HashMap<String, f64>,
+    large_trades: Vec<LargeTrade>,
     token_names_cache: HashMap<String, String>,
-    price_cache: HashMap<String, (f64, SystemTime)>,
+    /// Raydium token list indexed by uppercase symbol, with its fetch time.
+    token_index: Option<(HashMap<String, TokenInfo>, SystemTime)>,
+    token_list_url: String,
+    decimals_cache: HashMap<String, u8>,
+    price_cache: PriceCache,
     pub monitored_tokens: HashSet<String>,
+    pub minimum_transaction: Option<MinimumTransaction>,
+    pub min_alert_confidence: f64,
+    /// Commitment for signature and transaction fetches.
+    commitment: CommitmentConfig,
+    /// Each monitored token's per-cycle volume with the cycle's time, newest
+    /// last.
+    volume_history: HashMap<String, VecDeque<(SystemTime, f64)>>,
+    blacklisted_mints: HashSet<String>,
+    /// Transactions that only move these mints are stable-to-stable swaps
+    /// and are skipped while `exclude_stable_swaps` is set.
+    stable_mints: HashSet<String>,
+    exclude_stable_swaps: bool,
+    tracked_programs: Vec<Pubkey>,
+    program_cursors: HashMap<String, String>,
+    cursor_file: Option<PathBuf>,
+    /// Signatures already announced as large trades, oldest first, capped at
+    /// `MAX_ALERTED_SIGNATURES`.
+    alerted_signatures: VecDeque<String>,
+    alerted_file: Option<PathBuf>,
 }
 
 impl VolumeTracker {
     pub fn new(rpc_url: &str, min_volume: f64, max_volume: f64) -> Self {
+        Self::with_rpc_client(Arc::new(RpcClient::new(rpc_url.to_string())), min_volume, max_volume)
+    }
+
+    /// Builds a tracker on an existing client so every component talking to
+    /// the RPC shares one connection budget.
+    pub fn with_rpc_client(rpc_client: Arc<RpcClient>, min_volume: f64, max_volume: f64) -> Self {
         info!("Initializing VolumeTracker with min_volume: ${}, max_volume: ${}", min_volume, max_volume);
         Self {
-            rpc_client: Arc::new(RpcClient::new(rpc_url.to_string())),
+            rpc_endpoints: vec![RpcEndpoint::new(rpc_client)],
+            active_endpoint: 0,
             min_volume,
             max_volume,
-            volume_data: HashMap::new(),
             time_window: Duration::from_secs(900),
+            token_thresholds: HashMap::new(),
+            single_tx_alert_thresholds: HashMap::new(),
+            large_trades: Vec::new(),
             token_names_cache: HashMap::new(),
-            price_cache: HashMap::new(),
+            token_index: None,
+            token_list_url: RAYDIUM_TOKEN_LIST_URL.to_string(),
+            decimals_cache: HashMap::new(),
+            price_cache: PriceCache::new(DEFAULT_PRICE_CACHE_TTL),
             monitored_tokens: HashSet::new(),
+            minimum_transaction: None,
+            min_alert_confidence: 0.0,
+            commitment: CommitmentConfig::confirmed(),
+            volume_history: HashMap::new(),
+            blacklisted_mints: HashSet::new(),
+            stable_mints: DEFAULT_STABLE_MINTS.iter().map(|mint| mint.to_string()).collect(),
+            exclude_stable_swaps: true,
+            tracked_programs: DEFAULT_TRACKED_PROGRAMS
+                .iter()
+                .map(|program| Pubkey::from_str(program).expect("valid program id"))
+                .collect(),
+            program_cursors: HashMap::new(),
+            cursor_file: None,
+            alerted_signatures: VecDeque::new(),
+            alerted_file: None,
+        }
+    }
+
+    /// Parses a program id, or one of the names `raydium`, `openbook`, `orca`
+    /// and `jupiter`.
+    pub fn parse_tracked_program(program: &str) -> Result<Pubkey, String> {
+        let program_id = match program.trim().to_lowercase().as_str() {
+            "raydium" => RAYDIUM_AMM_PROGRAM,
+            "openbook" => OPENBOOK_PROGRAM,
+            "orca" => ORCA_WHIRLPOOL_PROGRAM,
+            "jupiter" => JUPITER_AGGREGATOR_PROGRAM,
+            _ => program.trim(),
+        };
+        Pubkey::from_str(program_id).map_err(|e| format!("Invalid tracked program '{}': {}", program, e))
+    }
+
+    pub fn with_tracked_programs(mut self, programs: Vec<Pubkey>) -> Self {
+        self.tracked_programs = programs;
+        self
+    }
+
+    /// Persists the newest processed signature per program to `path` and
+    /// resumes from it, so a restart does not rescan and re-alert on history
+    /// that was already handled.
+    pub fn with_cursor_file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<HashMap<String, String>>(&contents) {
+                Ok(cursors) => {
+                    info!("Resuming {} program cursors from {}", cursors.len(), path.display());
+                    self.program_cursors = cursors;
+                }
+                Err(e) => warn!("Ignoring unreadable cursor file {}: {}", path.display(), e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to read cursor file {}: {}", path.display(), e),
+        }
+        self.cursor_file = Some(path);
+        self
+    }
+
+    /// Newest processed signature for `program_id`, if any.
+    pub fn cursor_for(&self, program_id: &Pubkey) -> Option<&str> {
+        self.program_cursors.get(&program_id.to_string()).map(String::as_str)
+    }
+
+    fn save_cursors(&self) {
+        let Some(path) = &self.cursor_file else {
+            return;
+        };
+        let result = serde_json::to_string(&self.program_cursors)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| std::fs::write(path, contents).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Failed to save cursor file {}: {}", path.display(), e);
+        }
+    }
+
+    /// Persists the signatures of announced large trades to `path` so they
+    /// are not announced again after a restart.
+    pub fn with_alerted_signatures_file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<VecDeque<String>>(&contents) {
+                Ok(signatures) => {
+                    info!("Loaded {} alerted signatures from {}", signatures.len(), path.display());
+                    self.alerted_signatures = signatures;
+                }
+                Err(e) => warn!("Ignoring unreadable alerted signatures file {}: {}", path.display(), e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to read alerted signatures file {}: {}", path.display(), e),
+        }
+        self.alerted_file = Some(path);
+        self
+    }
+
+    fn was_alerted(&self, signature: &str) -> bool {
+        self.alerted_signatures.iter().any(|alerted| alerted == signature)
+    }
+
+    fn save_alerted_signatures(&self) {
+        let Some(path) = &self.alerted_file else {
+            return;
+        };
+        let result = serde_json::to_string(&self.alerted_signatures)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| std::fs::write(path, contents).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Failed to save alerted signatures file {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn with_stable_mints(mut self, mints: Vec<String>) -> Self {
+        self.stable_mints = mints.into_iter().collect();
+        self
+    }
+
+    pub fn with_stable_swap_exclusion(mut self, exclude: bool) -> Self {
+        self.exclude_stable_swaps = exclude;
+        self
+    }
+
+    /// True when every balance the transaction changed belongs to a stable
+    /// mint, e.g. a USDC→USDT swap.
+    fn is_stable_swap(&self, pre_balances: &[UiTransactionTokenBalance], post_balances: &[UiTransactionTokenBalance]) -> bool {
+        let mut changed_mints = Self::balance_changes(pre_balances, post_balances)
+            .into_iter()
+            .filter(BalanceChange::is_changed)
+            .map(|change| change.mint)
+            .peekable();
+        changed_mints.peek().is_some() && changed_mints.all(|mint| self.stable_mints.contains(mint))
+    }
+
+    pub fn with_price_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.price_cache.ttl = ttl;
+        self
+    }
+
+    /// Handle on the tracker's price cache.
+    pub fn price_cache(&self) -> PriceCache {
+        self.price_cache.clone()
+    }
+
+    pub fn with_blacklisted_mints(mut self, mints: impl IntoIterator<Item = String>) -> Self {
+        self.blacklisted_mints.extend(mints);
+        self
+    }
+
+    /// Blacklists a mint, dropping it from monitoring if it was being watched.
+    pub fn blacklist_mint(&mut self, mint: &str) {
+        self.blacklisted_mints.insert(mint.to_string());
+        self.remove_monitored_token(mint);
+        info!("Blacklisted token {}", mint);
+    }
+
+    pub fn is_blacklisted(&self, mint: &str) -> bool {
+        self.blacklisted_mints.contains(mint)
+    }
+
+    /// Adds endpoints to fail over to, in order, after the primary one.
+    pub fn with_fallback_rpc_urls(mut self, urls: Vec<String>) -> Self {
+        self.rpc_endpoints.extend(
+            urls.into_iter().map(|url| RpcEndpoint::new(Arc::new(RpcClient::new(url))))
+        );
+        self
+    }
+
+    /// Client for the endpoint currently in use.
+    pub fn rpc_client(&self) -> Arc<RpcClient> {
+        self.rpc_endpoints[self.active_endpoint].client.clone()
+    }
+
+    /// Runs `request` against the active endpoint. After
+    /// `RPC_FAILOVER_THRESHOLD` consecutive `BotError::RpcUnavailable`
+    /// failures the endpoint is marked unhealthy for `RPC_UNHEALTHY_COOLDOWN`
+    /// and the request is retried on the next endpoint, so callers only see
+    /// such an error once every endpoint has been tried or the active one has
+    /// not failed repeatedly yet. Any other error is an answer from a working
+    /// endpoint and is returned as is.
+    fn call_rpc<T>(
+        &mut self,
+        request: impl Fn(&RpcClient) -> Result<T, BotError>
+    ) -> Result<T, BotError> {
+        let endpoint_count = self.rpc_endpoints.len();
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let endpoint = &mut self.rpc_endpoints[self.active_endpoint];
+            match request(&endpoint.client) {
+                Err(e @ BotError::RpcUnavailable(_)) => {
+                    endpoint.consecutive_failures += 1;
+                    if endpoint.consecutive_failures < RPC_FAILOVER_THRESHOLD
+                        || attempts >= endpoint_count
+                    {
+                        return Err(e);
+                    }
+                    warn!("RPC endpoint {} failed {} times in a row, failing over: {}",
+                        endpoint.client.url(), endpoint.consecutive_failures, e);
+                    endpoint.unhealthy_until = Some(Instant::now() + RPC_UNHEALTHY_COOLDOWN);
+                    self.rotate_endpoint();
+                }
+                result => {
+                    endpoint.consecutive_failures = 0;
+                    endpoint.unhealthy_until = None;
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Moves to the next healthy endpoint, or simply the next one when all
+    /// are cooling down.
+    fn rotate_endpoint(&mut self) {
+        let count = self.rpc_endpoints.len();
+        let next = (1..count)
+            .map(|offset| (self.active_endpoint + offset) % count)
+            .find(|&index| self.rpc_endpoints[index].is_healthy())
+            .unwrap_or((self.active_endpoint + 1) % count);
+        let endpoint = &mut self.rpc_endpoints[next];
+        endpoint.consecutive_failures = 0;
+        info!("Switched RPC endpoint to {}", endpoint.client.url());
+        self.active_endpoint = next;
+    }
+
+    pub fn tracked_programs(&self) -> &[Pubkey] {
+        &self.tracked_programs
+    }
+
+    pub fn websocket_url(&self) -> String {
+        let rpc_url = self.rpc_endpoints[self.active_endpoint].client.url();
+        if let Some(rest) = rpc_url.strip_prefix("https://") {
+            format!("wss://{}", rest)
+        } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+            format!("ws://{}", rest)
+        } else {
+            rpc_url
+        }
+    }
+
+    /// Switches the commitment used for fetches from the next tracking cycle
+    /// on; stream-mode log subscriptions stay at `confirmed`. `processed` is
+    /// rejected because getSignaturesForAddress and getTransaction do not
+    /// support it.
+    pub fn set_commitment(&mut self, commitment: &str) -> Result<(), String> {
+        let commitment = match commitment.trim().to_lowercase().as_str() {
+            "confirmed" => CommitmentConfig::confirmed(),
+            "finalized" => CommitmentConfig::finalized(),
+            "processed" => {
+                return Err("'processed' is not supported by the transaction history RPC calls, use 'confirmed' or 'finalized'".to_string());
+            }
+            other => return Err(format!("Unknown commitment '{}', expected 'confirmed' or 'finalized'", other)),
+        };
+        self.commitment = commitment;
+        info!("RPC commitment set to {:?}", commitment.commitment);
+        Ok(())
+    }
+
+    pub fn commitment(&self) -> CommitmentConfig {
+        self.commitment
+    }
+
+    pub fn set_min_alert_confidence(&mut self, min_confidence: f64) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&min_confidence) {
+            return Err(format!("Confidence must be between 0 and 1, got {}", min_confidence));
         }
+        self.min_alert_confidence = min_confidence;
+        info!("Updated minimum alert confidence to {:.2}", min_confidence);
+        Ok(())
+    }
+
+    pub fn with_minimum_transaction(mut self, minimum: MinimumTransaction) -> Self {
+        self.minimum_transaction = Some(minimum);
+        self
+    }
+
+    pub fn set_minimum_transaction(&mut self, minimum: Option<MinimumTransaction>) {
+        self.minimum_transaction = minimum;
+        info!("Updated minimum transaction to {:?}", minimum);
     }
 
-    pub async fn add_monitored_token(&mut self, token_symbol: &str) -> Result<TokenInfo, Box<dyn std::error::Error + Send + Sync>> {
-        let token_info = self.get_token_info(token_symbol).await?;
+    pub async fn add_monitored_token(&mut self, token: &str) -> Result<TokenInfo, BotError> {
+        let token_info = self.resolve_token(token).await?;
+        if self.is_blacklisted(&token_info.address) {
+            return Err(BotError::TokenLookup(format!("Token {} ({}) is blacklisted", token_info.symbol, token_info.address)));
+        }
+        // Everything fallible is done above; the threshold is recorded before
+        // the token becomes visible to tracking, so a monitored token always
+        // has one.
+        let default_threshold = self.default_threshold();
+        self.token_thresholds.entry(token_info.address.clone()).or_insert(default_threshold);
+        self.token_names_cache.insert(token_info.address.clone(), token_info.symbol.clone());
         self.monitored_tokens.insert(token_info.address.clone());
         info!("Added token {} ({}) to monitoring", token_info.symbol, token_info.address);
         Ok(token_info)
     }
 
-    pub fn set_token_volume_threshold(&mut self, token_address: String, min: f64, max: f64, timeframe: u64) {
-        self.min_volume = min;
-        self.max_volume = max;
-        self.time_window = Duration::from_secs(timeframe * 60);
+    /// Converts a user-supplied timeframe in minutes, rejecting zero and
+    /// values too large to represent in seconds.
+    fn timeframe_window(timeframe: u64) -> Result<Duration, String> {
+        if timeframe == 0 {
+            return Err("Timeframe must be at least 1 minute".to_string());
+        }
+        timeframe
+            .checked_mul(60)
+            .map(Duration::from_secs)
+            .ok_or_else(|| format!("Timeframe of {} minutes is too large", timeframe))
+    }
+
+    pub fn set_token_volume_threshold(&mut self, token_address: String, min: f64, max: f64, timeframe: u64) -> Result<(), String> {
+        let time_window = Self::timeframe_window(timeframe)?;
         info!("Updated volume thresholds for token {}: min=${}, max=${}, timeframe={}min",
             token_address, min, max, timeframe);
+        self.token_thresholds.insert(token_address, VolumeThreshold {
+            min_volume: min,
+            max_volume: max,
+            time_window,
+        });
+        Ok(())
+    }
+
+    /// Sets the thresholds inherited by tokens added from now on; tokens
+    /// already monitored keep their own.
+    pub fn set_default_volume(&mut self, min: f64, max: f64, timeframe: u64) -> Result<(), String> {
+        self.time_window = Self::timeframe_window(timeframe)?;
+        self.min_volume = min;
+        self.max_volume = max;
+        info!("Updated default volume thresholds: min=${}, max=${}, timeframe={}min", min, max, timeframe);
+        Ok(())
+    }
+
+    /// Alerts on any single transaction worth at least `threshold` USD in
+    /// `token_address`, regardless of cumulative volume. `None` disables it.
+    pub fn set_single_tx_alert_threshold(&mut self, token_address: String, threshold: Option<f64>) {
+        match threshold {
+            Some(threshold) => {
+                info!("Single-transaction alert threshold for {} set to ${}", token_address, threshold);
+                self.single_tx_alert_thresholds.insert(token_address, threshold);
+            }
+            None => {
+                info!("Single-transaction alerts disabled for {}", token_address);
+                self.single_tx_alert_thresholds.remove(&token_address);
+            }
+        }
+    }
+
+    /// Drains the large trades found since the last call, recording their
+    /// signatures so the same transaction is never announced twice.
+    pub fn take_large_trades(&mut self) -> Vec<LargeTrade> {
+        let trades = std::mem::take(&mut self.large_trades);
+        if trades.is_empty() {
+            return trades;
+        }
+
+        for trade in &trades {
+            if !self.was_alerted(&trade.signature) {
+                self.alerted_signatures.push_back(trade.signature.clone());
+            }
+        }
+        while self.alerted_signatures.len() > MAX_ALERTED_SIGNATURES {
+            self.alerted_signatures.pop_front();
+        }
+        self.save_alerted_signatures();
+        trades
+    }
+
+    pub fn default_threshold(&self) -> VolumeThreshold {
+        VolumeThreshold {
+            min_volume: self.min_volume,
+            max_volume: self.max_volume,
+            time_window: self.time_window,
+        }
+    }
+
+    /// Threshold applied to `token_address`, falling back to the defaults so
+    /// a token is never silently ignored for lack of one.
+    pub fn threshold_for(&self, token_address: &str) -> VolumeThreshold {
+        self.token_thresholds
+            .get(token_address)
+            .copied()
+            .unwrap_or_else(|| self.default_threshold())
     }
 
     pub fn remove_monitored_token(&mut self, token_address: &str) {
+        self.token_thresholds.remove(token_address);
+        self.single_tx_alert_thresholds.remove(token_address);
         if self.monitored_tokens.remove(token_address) {
             info!("Removed token {} from monitoring", token_address);
         }
     }
 
     pub fn get_monitored_tokens_list(&self) -> String {
-        let tokens: Vec<_> = self.monitored_tokens
+        let mut entries: Vec<(&str, &String)> = self.monitored_tokens
             .iter()
-            .filter_map(|addr| {
-                self.token_names_cache.get(addr).map(|name| format!("{} ({})", name, addr))
+            .map(|addr| {
+                let name = self.token_names_cache.get(addr).map_or(addr.as_str(), |name| name.as_str());
+                (name, addr)
+            })
+            .collect();
+        // HashSet order changes between calls; sort by symbol, then address.
+        entries.sort();
+
+        let tokens: Vec<String> = entries
+            .into_iter()
+            .map(|(name, addr)| {
+                if name == addr {
+                    addr.clone()
+                } else {
+                    format!("{} ({})", name, addr)
+                }
             })
             .collect();
 
@@ -118,181 +784,328 @@ impl VolumeTracker {
         }
     }
 
-    pub async fn track_trades(&mut self) -> Result<Vec<TradingVolume>, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn track_trades(&mut self) -> Result<Vec<TradingVolume>, BotError> {
         if self.monitored_tokens.is_empty() {
             info!("No tokens being monitored");
             return Ok(Vec::new());
         }
 
-        info!("Starting trade tracking cycle");
+        info!("Starting trade tracking cycle across {} programs", self.tracked_programs.len());
         let mut all_volumes = Vec::new();
+        // Aggregator swaps show up under both the aggregator and the pool
+        // program, so each signature is only analyzed once per cycle.
+        let mut seen_signatures = HashSet::new();
 
-        // Track DEX trades
-        info!("Tracking DEX trades...");
-        let dex_volumes = self.track_dex_trades().await?;
-        info!("Found {} DEX trading volumes", dex_volumes.len());
-        all_volumes.extend(dex_volumes);
-
-        // Track AMM swaps
-        info!("Tracking AMM swaps...");
-        let swap_volumes = self.track_amm_swaps().await?;
-        info!("Found {} AMM swap volumes", swap_volumes.len());
-
-        // Merge volumes
-        for swap_vol in swap_volumes {
-            if let Some(existing) = all_volumes.iter_mut().find(|v| v.token_address == swap_vol.token_address) {
-                info!("Merging volumes for {}: Adding ${:.2} from swaps",
-                    swap_vol.token_name, swap_vol.total_volume);
-                existing.total_volume += swap_vol.total_volume;
-                existing.swap_count += swap_vol.swap_count;
-                existing.average_trade_size = existing.total_volume /
-                    (existing.trade_count as f64 + existing.swap_count as f64);
-            } else {
-                info!("Adding new swap volume for {}: ${:.2}",
-                    swap_vol.token_name, swap_vol.total_volume);
-                all_volumes.push(swap_vol);
+        for program_id in self.tracked_programs.clone() {
+            // A failing program is logged and skipped so the other programs'
+            // data still makes it into this cycle.
+            match self.track_program(&program_id, &mut seen_signatures, &mut all_volumes).await {
+                Ok(analyzed) => info!("Analyzed {} transactions for program {}", analyzed, program_id),
+                Err(e) => error!("Failed to track program {}, continuing with partial data: {}", program_id, e),
             }
         }
 
+        self.record_cycle_volumes(&all_volumes);
         self.clean_old_data();
         info!("Completed trade tracking cycle. Found {} total volumes", all_volumes.len());
         Ok(all_volumes)
     }
 
-    async fn track_dex_trades(&self) -> Result<Vec<TradingVolume>, Box<dyn std::error::Error + Send + Sync>> {
-        let dex_program_id = Pubkey::from_str(RAYDIUM_DEX_PROGRAM)?;
+    async fn track_program(
+        &mut self,
+        program_id: &Pubkey,
+        seen_signatures: &mut HashSet<String>,
+        hot_volumes: &mut Vec<TradingVolume>
+    ) -> Result<usize, BotError> {
+        let is_orderbook = program_id.to_string() == OPENBOOK_PROGRAM;
         let mut all_signatures = Vec::new();
-        let mut before = None;
+        let mut before: Option<Signature> = None;
+        let until = self.program_cursors
+            .get(&program_id.to_string())
+            .map(|signature| Self::parse_signature(signature))
+            .transpose()?;
 
+        let commitment = self.commitment;
         loop {
-            let batch = self.rpc_client.get_signatures_for_address_with_config(
-                &dex_program_id,
+            let batch = match self.call_rpc(|rpc_client| Ok(rpc_client.get_signatures_for_address_with_config(
+                program_id,
                 GetConfirmedSignaturesForAddress2Config {
-                    before: before.map(|s| s.signature.clone()),
-                    until: None,
+                    before,
+                    until,
                     limit: Some(100),
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    min_context_slot: None,
+                    commitment: Some(commitment),
                 }
-            )?;
+            )?)) {
+                Ok(batch) => batch,
+                Err(e) if !all_signatures.is_empty() => {
+                    warn!("Failed to fetch next signature batch, analyzing {} already fetched: {}",
+                        all_signatures.len(), e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
 
             if batch.is_empty() {
                 break;
             }
 
             info!("Fetched batch of {} transactions", batch.len());
-            before = Some(batch.last().unwrap().signature.clone());
+            before = Some(Self::parse_signature(&batch.last().unwrap().signature)?);
             all_signatures.extend(batch);
 
-            if all_signatures.len() >= 1000 {
+            if all_signatures.len() >= MAX_SIGNATURES_PER_PROGRAM {
                 break;
             }
         }
 
-        info!("Found {} DEX transactions to analyze", all_signatures.len());
-        let mut hot_volumes = Vec::new();
+        // Signatures come newest first; the head becomes the next cursor.
+        let newest_signature = all_signatures.first().map(|sig_info| sig_info.signature.clone());
+        let signatures: Vec<String> = all_signatures
+            .into_iter()
+            .map(|sig_info| sig_info.signature)
+            .filter(|signature| seen_signatures.insert(signature.clone()))
+            .collect();
+        info!("Found {} new transactions to analyze for program {}", signatures.len(), program_id);
 
-        for (i, sig_info) in all_signatures.iter().enumerate() {
-            if i % 50 == 0 {
-                info!("Processing batch {}-{}", i, i+50);
-            }
+        self.analyze_signatures(&signatures, is_orderbook, hot_volumes).await;
+        if let Some(signature) = newest_signature {
+            self.program_cursors.insert(program_id.to_string(), signature);
+            self.save_cursors();
+        }
+        Ok(signatures.len())
+    }
 
-            let tx = self.rpc_client.get_transaction_with_config(
-                &sig_info.signature.parse()?,
-                RpcTransactionConfig {
-                    encoding: Some(UiTransactionEncoding::Json),
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    max_supported_transaction_version: Some(0),
-                },
-            )?;
-
-            if let Some(meta) = tx.transaction.meta {
-                if let Some(token_balances) = <OptionSerializer<Vec<UiTransactionTokenBalance>> as Into<Option<Vec<UiTransactionTokenBalance>>>>::into(meta.pre_token_balances) {
-                    self.process_token_balances(&token_balances, meta.post_token_balances.unwrap(), &mut hot_volumes).await?;
-                }
+    /// Analyzes signatures collected by a `MonitorMode::Stream` subscription,
+    /// sharing the balance processing used by the polling path.
+    pub async fn track_streamed_signatures(
+        &mut self,
+        signatures: Vec<(Pubkey, String)>
+    ) -> Result<Vec<TradingVolume>, BotError> {
+        if self.monitored_tokens.is_empty() {
+            info!("No tokens being monitored");
+            return Ok(Vec::new());
+        }
+
+        info!("Analyzing {} streamed transactions", signatures.len());
+        let mut all_volumes = Vec::new();
+        let mut seen_signatures = HashSet::new();
+
+        for (program_id, signature) in signatures {
+            if !seen_signatures.insert(signature.clone()) {
+                continue;
             }
+            let is_orderbook = program_id.to_string() == OPENBOOK_PROGRAM;
+            self.analyze_signatures(std::slice::from_ref(&signature), is_orderbook, &mut all_volumes).await;
         }
 
-        Ok(hot_volumes)
-     }
+        self.record_cycle_volumes(&all_volumes);
+        self.clean_old_data();
+        info!("Completed streamed tracking cycle. Found {} total volumes", all_volumes.len());
+        Ok(all_volumes)
+    }
 
-    async fn track_amm_swaps(&self) -> Result<Vec<TradingVolume>, Box<dyn std::error::Error + Send + Sync>> {
-        let amm_program_id = Pubkey::from_str(RAYDIUM_AMM_PROGRAM)?;
-        let signatures = self.rpc_client.get_signatures_for_address(&amm_program_id)?;
-        info!("Found {} AMM transactions to analyze", signatures.len());
+    /// Forwards the signature of every successful transaction mentioning
+    /// `program_id` to `sender` until the subscription drops or the receiver
+    /// is closed. Signatures arriving while the channel is full are dropped,
+    /// so a slow cycle cannot make the backlog grow without bound.
+    pub async fn stream_program_signatures(
+        ws_url: &str,
+        program_id: Pubkey,
+        sender: SignatureSender
+    ) -> Result<(), BotError> {
+        let client = PubsubClient::new(ws_url).await?;
+        let (mut stream, unsubscribe) = client.logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        ).await?;
+        info!("Subscribed to logs for program {}", program_id);
 
-        let mut hot_volumes = Vec::new();
+        while let Some(response) = stream.next().await {
+            if response.value.err.is_some() {
+                continue;
+            }
+            if !sender.send(program_id, response.value.signature) {
+                break;
+            }
+        }
 
-        for (i, sig_info) in signatures.iter().enumerate() {
-            info!("Processing AMM transaction {}/{}", i + 1, signatures.len());
-            let tx = self.rpc_client.get_transaction_with_config(
-                &sig_info.signature.parse()?,
-                RpcTransactionConfig {
-                    encoding: Some(UiTransactionEncoding::Json),
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    max_supported_transaction_version: Some(0),
-                },
-            )?;
+        unsubscribe().await;
+        info!("Logs subscription for program {} ended", program_id);
+        Ok(())
+    }
 
-            if let Some(meta) = tx.transaction.meta {
-                if let Some(token_balances) = <OptionSerializer<Vec<UiTransactionTokenBalance>> as Into<Option<Vec<UiTransactionTokenBalance>>>>::into(meta.pre_token_balances) {
-                    self.process_token_balances(&token_balances, meta.post_token_balances.unwrap(), &mut hot_volumes).await?;
-                }
+    fn parse_signature(signature: &str) -> Result<Signature, BotError> {
+        Signature::from_str(signature)
+            .map_err(|e| BotError::Rpc(format!("Invalid signature {} from RPC: {}", signature, e)))
+    }
+
+    /// Fetches and processes each signature. A transaction that cannot be
+    /// fetched or parsed is logged and skipped, so one bad signature never
+    /// holds back the rest of the batch or the program cursor.
+    async fn analyze_signatures(
+        &mut self,
+        signatures: &[String],
+        is_orderbook: bool,
+        hot_volumes: &mut Vec<TradingVolume>
+    ) {
+        for (i, signature) in signatures.iter().enumerate() {
+            if i % 50 == 0 {
+                info!("Processing batch {}-{}", i, i+50);
+            }
+
+            if let Err(e) = self.analyze_signature(signature, is_orderbook, hot_volumes).await {
+                warn!("Skipping transaction {}: {}", signature, e);
             }
         }
+    }
+
+    async fn analyze_signature(
+        &mut self,
+        signature: &str,
+        is_orderbook: bool,
+        hot_volumes: &mut Vec<TradingVolume>
+    ) -> Result<(), BotError> {
+        let signature_id = Self::parse_signature(signature)?;
+        let commitment = self.commitment;
+        let tx = self.call_rpc(|rpc_client| Ok(rpc_client.get_transaction_with_config(
+            &signature_id,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(commitment),
+                max_supported_transaction_version: Some(0),
+            },
+        )?))?;
+
+        let Some(meta) = tx.transaction.meta else {
+            return Ok(());
+        };
+        let pre_balances: Option<Vec<UiTransactionTokenBalance>> = meta.pre_token_balances.into();
+        let post_balances: Option<Vec<UiTransactionTokenBalance>> = meta.post_token_balances.into();
+        if let (Some(pre_balances), Some(post_balances)) = (pre_balances, post_balances) {
+            self.process_token_balances(signature, &pre_balances, &post_balances, is_orderbook, hot_volumes).await?;
+        }
+        Ok(())
+    }
 
-        Ok(hot_volumes)
+    /// Pairs pre and post balances by account. The RPC does not keep the two
+    /// lists in the same order or of the same length.
+    fn balance_changes<'a>(
+        pre_balances: &'a [UiTransactionTokenBalance],
+        post_balances: &'a [UiTransactionTokenBalance]
+    ) -> Vec<BalanceChange<'a>> {
+        let mut changes: Vec<BalanceChange<'a>> = post_balances
+            .iter()
+            .map(|post| BalanceChange {
+                mint: &post.mint,
+                pre: pre_balances
+                    .iter()
+                    .find(|pre| pre.account_index == post.account_index)
+                    .map(|pre| &pre.ui_token_amount),
+                post: Some(&post.ui_token_amount),
+            })
+            .collect();
+        changes.extend(
+            pre_balances
+                .iter()
+                .filter(|pre| !post_balances.iter().any(|post| post.account_index == pre.account_index))
+                .map(|pre| BalanceChange { mint: &pre.mint, pre: Some(&pre.ui_token_amount), post: None })
+        );
+        changes
     }
 
     async fn process_token_balances(
-        &self,
+        &mut self,
+        signature: &str,
         pre_balances: &[UiTransactionTokenBalance],
-        post_balances: Vec<UiTransactionTokenBalance>,
+        post_balances: &[UiTransactionTokenBalance],
+        is_orderbook: bool,
         hot_volumes: &mut Vec<TradingVolume>
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        for (pre, post) in pre_balances.iter().zip(post_balances) {
+    ) -> Result<(), BotError> {
+        if self.exclude_stable_swaps && self.is_stable_swap(pre_balances, post_balances) {
+            debug!("Skipping stable-to-stable swap {}", signature);
+            return Ok(());
+        }
+
+        for change in Self::balance_changes(pre_balances, post_balances) {
+            let mint = change.mint;
             // Skip if not monitoring this token
-            if !self.monitored_tokens.contains(&post.mint) {
+            if !self.monitored_tokens.contains(mint) || self.is_blacklisted(mint) {
                 continue;
             }
 
-            let amount_change = (post.ui_token_amount.ui_amount.unwrap_or(0.0)
-                - pre.ui_token_amount.ui_amount.unwrap_or(0.0)).abs();
+            let decimals = *self.decimals_cache
+                .entry(mint.to_string())
+                .or_insert(change.decimals());
+            let ui_amount = |side: Option<&UiTokenAmount>| side.map_or(Some(0.0), |amount| Self::ui_amount(amount, decimals));
+            let (Some(pre_amount), Some(post_amount)) = (ui_amount(change.pre), ui_amount(change.post)) else {
+                warn!(
+                    "Skipping balance for {} with unparseable amount (pre: {:?}, post: {:?})",
+                    mint, BalanceChange::amount(change.pre), BalanceChange::amount(change.post)
+                );
+                continue;
+            };
+
+            if let Some(MinimumTransaction::Raw(min_raw)) = self.minimum_transaction {
+                let raw_change = Self::raw_amount(BalanceChange::amount(change.post))
+                    .zip(Self::raw_amount(BalanceChange::amount(change.pre)))
+                    .map(|(post_raw, pre_raw)| post_raw.abs_diff(pre_raw));
+                if raw_change.is_none_or(|change| change < min_raw) {
+                    continue;
+                }
+            }
+
+            let amount_change = (post_amount - pre_amount).abs();
 
-            let token_price = match self.get_token_price(&post.mint).await {
+            let token_price = match self.price_cache.get(mint).await {
                 Ok(price) => price,
                 Err(e) => {
-                    warn!("Failed to get price for token {}: {}", post.mint, e);
+                    warn!("Failed to get price for token {}: {}", mint, e);
                     continue;
                 }
             };
 
             let trade_value = amount_change * token_price;
-            if trade_value >= self.min_volume && trade_value <= self.max_volume {
-                let token_name = match self.get_token_name(&post.mint).await {
+            self.check_single_tx_alert(signature, mint, trade_value).await;
+            if let Some(MinimumTransaction::Usd(min_usd)) = self.minimum_transaction {
+                if trade_value < min_usd {
+                    continue;
+                }
+            }
+
+            let threshold = self.threshold_for(mint);
+            if trade_value >= threshold.min_volume && trade_value <= threshold.max_volume {
+                let token_name = match self.get_token_name(mint).await {
                     Ok(name) => name,
                     Err(e) => {
-                        warn!("Failed to get name for token {}: {}", post.mint, e);
-                        post.mint.clone()
+                        warn!("Failed to get name for token {}: {}", mint, e);
+                        mint.to_string()
                     }
                 };
 
-                if let Some(existing) = hot_volumes.iter_mut().find(|v| v.token_address == post.mint) {
+                if let Some(existing) = hot_volumes.iter_mut().find(|v| v.token_address == mint) {
                     existing.total_volume += trade_value;
-                    existing.trade_count += 1;
+                    if is_orderbook {
+                        existing.trade_count += 1;
+                    } else {
+                        existing.swap_count += 1;
+                    }
                     existing.average_trade_size = existing.total_volume /
                         (existing.trade_count as f64 + existing.swap_count as f64);
+                    existing.largest_trade = existing.largest_trade.max(trade_value);
                     existing.last_update = SystemTime::now();
                     info!("Updated volume for {}: ${:.2}", token_name, trade_value);
                 } else {
                     let token_name_clone = token_name.clone();  // Clone here
                     hot_volumes.push(TradingVolume {
-                        token_address: post.mint.clone(),
+                        token_address: mint.to_string(),
                         token_name,
                         total_volume: trade_value,
-                        trade_count: 1,
-                        swap_count: 0,
+                        trade_count: u32::from(is_orderbook),
+                        swap_count: u32::from(!is_orderbook),
                         average_trade_size: trade_value,
+                        largest_trade: trade_value,
                         last_update: SystemTime::now(),
                     });
                     info!("New trade tracked for {}: ${:.2}", token_name_clone, trade_value);
@@ -302,7 +1115,73 @@ impl VolumeTracker {
         Ok(())
     }
 
-    async fn get_token_price(&self, mint: &str) -> Result<f64, Box<dyn std::error::Error + Send + Sync>> {
+    /// Records a `LargeTrade` when `trade_value` crosses the token's
+    /// single-transaction threshold. Several balance entries of one
+    /// transaction can move the same mint, so only the largest is kept.
+    async fn check_single_tx_alert(&mut self, signature: &str, mint: &str, trade_value: f64) {
+        let Some(&threshold) = self.single_tx_alert_thresholds.get(mint) else {
+            return;
+        };
+        if trade_value < threshold || self.was_alerted(signature) {
+            return;
+        }
+
+        if let Some(existing) = self.large_trades
+            .iter_mut()
+            .find(|trade| trade.signature == signature && trade.token_address == mint)
+        {
+            existing.value = existing.value.max(trade_value);
+            return;
+        }
+
+        let token_name = match self.get_token_name(mint).await {
+            Ok(name) => name,
+            Err(e) => {
+                warn!("Failed to get name for token {}: {}", mint, e);
+                mint.to_string()
+            }
+        };
+        info!("Large trade of ${:.2} for {} in {}", trade_value, token_name, signature);
+        self.large_trades.push(LargeTrade {
+            token_address: mint.to_string(),
+            token_name,
+            signature: signature.to_string(),
+            value: trade_value,
+        });
+    }
+
+    /// Base-unit amount, or `None` when the RPC returned an empty or
+    /// non-numeric string.
+    fn raw_amount(amount: &str) -> Option<u64> {
+        amount.parse::<u64>().ok()
+    }
+
+    /// Decimal-adjusted amount, derived from the raw amount when the RPC
+    /// leaves `ui_amount` null. `None` means neither is usable, which callers
+    /// must skip rather than count as zero.
+    fn ui_amount(amount: &UiTokenAmount, decimals: u8) -> Option<f64> {
+        amount.ui_amount.or_else(|| {
+            Self::raw_amount(&amount.amount)
+                .map(|raw| raw as f64 / 10f64.powi(i32::from(decimals)))
+        })
+    }
+
+    /// Decimals for `mint`, cached after the first balance entry or mint
+    /// account lookup.
+    pub fn get_token_decimals(&mut self, mint: &str) -> Result<u8, BotError> {
+        if let Some(decimals) = self.decimals_cache.get(mint) {
+            return Ok(*decimals);
+        }
+
+        let mint_address = Pubkey::from_str(mint)
+            .map_err(|e| BotError::TokenLookup(format!("Invalid mint {}: {}", mint, e)))?;
+        let decimals = self.call_rpc(|rpc_client| Ok(rpc_client.get_token_supply(&mint_address)?))?.decimals;
+        self.decimals_cache.insert(mint.to_string(), decimals);
+        Ok(decimals)
+    }
+
+    /// Uncached USD price lookup against the Raydium price API.
+    pub async fn fetch_token_price(mint: &str) -> Result<f64, BotError> {
         let url = format!(
             "https://api.raydium.io/v2/main/price?tokens={}",
             mint
@@ -311,84 +1190,1160 @@ impl VolumeTracker {
         let client = reqwest::Client::new();
         let response = client.get(&url)
             .send()
-            .await?
+            .await
+            .map_err(|e| BotError::Price(e.to_string()))?
             .json::<RaydiumPriceResponse>()
-            .await?;
+            .await
+            .map_err(|e| BotError::Price(e.to_string()))?;
 
         if let Some(token_data) = response.data.get(mint) {
             Ok(token_data.price)
         } else {
-            Err("Price not found on Raydium".into())
+            Err(BotError::Price(format!("Price for {} not found on Raydium", mint)))
         }
     }
 
-    async fn get_token_name(&self, mint: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    async fn get_token_name(&mut self, mint: &str) -> Result<String, BotError> {
         if let Some(name) = self.token_names_cache.get(mint) {
             return Ok(name.clone());
         }
-        Ok(mint.to_string())
+
+        // A failed lookup falls back to the mint without caching it, so a
+        // transient RPC error does not leave the token unnamed for good.
+        match self.fetch_metadata_name(mint) {
+            Ok(name) => {
+                self.token_names_cache.insert(mint.to_string(), name.clone());
+                Ok(name)
+            }
+            Err(e) => {
+                debug!("No Metaplex metadata for {}: {}", mint, e);
+                Ok(mint.to_string())
+            }
+        }
+    }
+
+    /// Derives the Metaplex token metadata PDA for a mint.
+    pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+        let program_id = Pubkey::from_str(METAPLEX_METADATA_PROGRAM).expect("valid program id");
+        Pubkey::find_program_address(
+            &[b"metadata", program_id.as_ref(), mint.as_ref()],
+            &program_id,
+        ).0
+    }
+
+    /// Reads the name and symbol from a Metaplex metadata account. The layout
+    /// is a 1-byte key, the update authority and mint pubkeys, then the
+    /// borsh-encoded (u32 length-prefixed, NUL-padded) name and symbol.
+    pub fn parse_metadata_name(data: &[u8]) -> Option<(String, String)> {
+        fn read_string(data: &[u8], offset: &mut usize) -> Option<String> {
+            let len_bytes: [u8; 4] = data.get(*offset..*offset + 4)?.try_into().ok()?;
+            let len = u32::from_le_bytes(len_bytes) as usize;
+            *offset += 4;
+            let bytes = data.get(*offset..*offset + len)?;
+            *offset += len;
+            Some(String::from_utf8_lossy(bytes).trim_end_matches('\0').trim().to_string())
+        }
+
+        let mut offset = 1 + 32 + 32;
+        let name = read_string(data, &mut offset)?;
+        let symbol = read_string(data, &mut offset)?;
+        Some((name, symbol))
+    }
+
+    fn fetch_metadata_name(&mut self, mint: &str) -> Result<String, BotError> {
+        let mint_address = Pubkey::from_str(mint)
+            .map_err(|e| BotError::TokenLookup(format!("Invalid mint {}: {}", mint, e)))?;
+        let metadata_address = Self::metadata_address(&mint_address);
+        let data = self.call_rpc(|rpc_client| Ok(rpc_client.get_account_data(&metadata_address)?))?;
+        let (name, symbol) = Self::parse_metadata_name(&data)
+            .ok_or_else(|| BotError::TokenLookup("Malformed Metaplex metadata account".to_string()))?;
+
+        if !symbol.is_empty() {
+            Ok(symbol)
+        } else if !name.is_empty() {
+            Ok(name)
+        } else {
+            Err(BotError::TokenLookup("Metaplex metadata has no name or symbol".to_string()))
+        }
     }
 
-    pub async fn get_token_info(&self, token_symbol: &str) -> Result<TokenInfo, Box<dyn std::error::Error + Send + Sync>> {
-        let url = "https://api-v3.raydium.io/mint/list";
+    /// Resolves a symbol or a mint address. Symbols are looked up in the
+    /// Raydium token list; a mint address is accepted even when Raydium does
+    /// not list it, taking its name from Metaplex metadata when available.
+    pub async fn resolve_token(&mut self, token: &str) -> Result<TokenInfo, BotError> {
+        if Pubkey::from_str(token).is_err() {
+            return self.get_token_info(token).await;
+        }
+
+        if let Err(e) = self.refresh_token_index().await {
+            warn!("Raydium token list unavailable, resolving {} from metadata: {}", token, e);
+        }
+        let listed = self.token_index
+            .as_ref()
+            .and_then(|(index, _)| index.values().find(|info| info.address == token))
+            .cloned();
+        if let Some(info) = listed {
+            return Ok(info);
+        }
+
+        // Only a mint has a supply, so this rejects wallet and program
+        // addresses and caches the decimals for balance processing.
+        self.get_token_decimals(token)
+            .map_err(|e| BotError::TokenLookup(format!("{} is not a token mint: {}", token, e)))?;
+        Ok(TokenInfo {
+            symbol: self.get_token_name(token).await?,
+            address: token.to_string(),
+        })
+    }
+
+    /// Looks up a token by symbol in the Raydium token list, which is
+    /// downloaded at most once per `TOKEN_LIST_TTL`.
+    pub async fn get_token_info(&mut self, token_symbol: &str) -> Result<TokenInfo, BotError> {
+        self.refresh_token_index().await?;
+        self.token_index
+            .as_ref()
+            .and_then(|(index, _)| index.get(&token_symbol.to_uppercase()))
+            .cloned()
+            .ok_or_else(|| BotError::TokenLookup(format!("Token {} not found on Raydium", token_symbol)))
+    }
+
+    async fn refresh_token_index(&mut self) -> Result<(), BotError> {
+        let is_fresh = self.token_index
+            .as_ref()
+            .is_some_and(|(_, fetched_at)| fetched_at.elapsed().is_ok_and(|age| age < TOKEN_LIST_TTL));
+        if !is_fresh {
+            let index = Self::fetch_token_index(&self.token_list_url).await?;
+            info!("Cached Raydium token list with {} symbols", index.len());
+            self.token_index = Some((index, SystemTime::now()));
+        }
+        Ok(())
+    }
+
+    async fn fetch_token_index(url: &str) -> Result<HashMap<String, TokenInfo>, BotError> {
         let client = reqwest::Client::new();
 
-        let response = client.get(url).send().await?;
-        info!("API Status: {}", response.status());
-
-        let text = response.text().await?;
-        info!("Raw response: {}", text);
-
-        // Parse the JSON after logging
-        let json: serde_json::Value = serde_json::from_str(&text)?;
-
-        if let Some(tokens) = json.get("data").and_then(|d| d.get("mintList").and_then(|m| m.as_array())) {
-            for token in tokens {
-                if let (Some(symbol), Some(address)) = (
-                    token.get("symbol").and_then(|s| s.as_str()),
-                    token.get("address").and_then(|a| a.as_str())
-                ) {
-                    if symbol.to_uppercase() == token_symbol.to_uppercase() {
-                        return Ok(TokenInfo {
-                            symbol: symbol.to_string(),
-                            address: address.to_string(),
-                        });
-                    }
+        let response = client.get(url)
+            .send()
+            .await
+            .map_err(|e| BotError::TokenLookup(e.to_string()))?;
+        let status = response.status();
+        info!("API Status: {}", status);
+
+        let text = response.text()
+            .await
+            .map_err(|e| BotError::TokenLookup(e.to_string()))?;
+        debug!("Raw response: {}", text);
+
+        Self::parse_token_list_response(status, &text)
+    }
+
+    /// Indexes a Raydium token list response, or explains why it is unusable,
+    /// so a failed request is never mistaken for a missing symbol.
+    fn parse_token_list_response(status: reqwest::StatusCode, text: &str) -> Result<HashMap<String, TokenInfo>, BotError> {
+        if !status.is_success() {
+            return Err(BotError::TokenLookup(format!("Raydium token list request failed with status {}", status)));
+        }
+
+        let json: serde_json::Value = serde_json::from_str(text)
+            .map_err(|e| BotError::TokenLookup(format!("Failed to parse Raydium token list response: {}", e)))?;
+
+        let tokens = json.get("data")
+            .and_then(|d| d.get("mintList"))
+            .and_then(|m| m.as_array())
+            .ok_or_else(|| BotError::TokenLookup("Unexpected Raydium token list response: missing data.mintList".to_string()))?;
+        Ok(Self::build_token_index(tokens))
+    }
+
+    /// Indexes token list entries by uppercase symbol, skipping entries
+    /// without a symbol or address.
+    fn build_token_index(tokens: &[serde_json::Value]) -> HashMap<String, TokenInfo> {
+        let mut index = HashMap::new();
+        for token in tokens {
+            if let (Some(symbol), Some(address)) = (
+                token.get("symbol").and_then(|s| s.as_str()),
+                token.get("address").and_then(|a| a.as_str())
+            ) {
+                // Symbols are not unique; the first listing wins, as it did
+                // with the old linear scan.
+                index.entry(symbol.to_uppercase()).or_insert_with(|| TokenInfo {
+                    symbol: symbol.to_string(),
+                    address: address.to_string(),
+                });
+            }
+        }
+        index
+    }
+
+    /// Appends this cycle's volume for every monitored token (zero when it had
+    /// no qualifying trades) to its history buffer.
+    fn record_cycle_volumes(&mut self, volumes: &[TradingVolume]) {
+        let now = SystemTime::now();
+        for token in &self.monitored_tokens {
+            let volume = volumes
+                .iter()
+                .find(|v| &v.token_address == token)
+                .map_or(0.0, |v| v.total_volume);
+            let history = self.volume_history.entry(token.clone()).or_default();
+            history.push_back((now, volume));
+            while history.len() > VOLUME_HISTORY_CYCLES {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// Ranks monitored tokens by volume change between the last two cycles
+    /// still inside their time window, largest increase first.
+    pub fn top_movers(&self, limit: usize) -> Vec<VolumeMover> {
+        let mut movers: Vec<VolumeMover> = self.volume_history
+            .iter()
+            .filter(|(token, _)| self.monitored_tokens.contains(*token))
+            .filter_map(|(token, history)| {
+                let len = history.len();
+                if len < 2 {
+                    return None;
+                }
+                let previous_volume = history[len - 2].1;
+                let current_volume = history[len - 1].1;
+                let change_pct = if previous_volume > 0.0 {
+                    (current_volume - previous_volume) / previous_volume * 100.0
+                } else if current_volume > 0.0 {
+                    f64::INFINITY
+                } else {
+                    0.0
+                };
+                Some(VolumeMover {
+                    token_address: token.clone(),
+                    token_name: self.token_names_cache.get(token).cloned().unwrap_or_else(|| token.clone()),
+                    previous_volume,
+                    current_volume,
+                    change_pct,
+                })
+            })
+            .collect();
+
+        movers.sort_by(|a, b| b.change_pct.total_cmp(&a.change_pct));
+        movers.truncate(limit);
+        movers
+    }
+
+    /// Clears the per-cycle volume history and pending large-trade alerts, for
+    /// one token or all of them, returning how many tokens had any. The
+    /// persisted alerted-signature log is kept so a reset never re-announces
+    /// trades.
+    pub fn reset_volume(&mut self, token_address: Option<&str>) -> usize {
+        let mut cleared: HashSet<String> = HashSet::new();
+        match token_address {
+            Some(address) => {
+                let had_history = self.volume_history.remove(address).is_some();
+                let had_trades = self.large_trades.iter().any(|trade| trade.token_address == address);
+                if had_history || had_trades {
+                    cleared.insert(address.to_string());
                 }
+                self.large_trades.retain(|trade| trade.token_address != address);
+            }
+            None => {
+                cleared.extend(self.volume_history.drain().map(|(address, _)| address));
+                cleared.extend(self.large_trades.drain(..).map(|trade| trade.token_address));
             }
         }
-        Err(format!("Token {} not found on Raydium", token_symbol).into())
+        info!("Reset volume data for {} tokens", cleared.len());
+        cleared.len()
     }
 
+    /// Drops history entries older than their token's time window, so a
+    /// cycle from before a long pause or outage is never compared against.
     fn clean_old_data(&mut self) {
         let now = SystemTime::now();
-        let initial_count = self.volume_data.len();
-        self.volume_data.retain(|_, v| {
-            if let Ok(duration) = now.duration_since(v.last_update) {
-                duration < self.time_window
-            } else {
-                false
-            }
-        });
-        let removed_count = initial_count - self.volume_data.len();
+        let thresholds = &self.token_thresholds;
+        let default_window = self.time_window;
+        let mut removed_count = 0;
+        for (address, history) in self.volume_history.iter_mut() {
+            let time_window = thresholds.get(address).map_or(default_window, |t| t.time_window);
+            let initial_len = history.len();
+            history.retain(|(recorded_at, _)| {
+                now.duration_since(*recorded_at).is_ok_and(|age| age < time_window)
+            });
+            removed_count += initial_len - history.len();
+        }
+        self.volume_history.retain(|_, history| !history.is_empty());
         if removed_count > 0 {
             info!("Cleaned {} old entries", removed_count);
         }
     }
+}
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::bot::telegram::WhaleBot;
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+    use solana_client::client_error::{ClientError, ClientErrorKind};
+    use solana_client::rpc_client::RpcClientConfig;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_account_decoder::{encode_ui_account, UiAccountEncoding};
+    use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+    use solana_sdk::account::Account;
+    use solana_transaction_status::option_serializer::OptionSerializer;
+    use std::sync::Mutex;
 
-    pub fn get_hot_pairs(&self) -> Vec<TradingVolume> {
-        let hot_pairs: Vec<_> = self.volume_data
-            .values()
-            .filter(|v|
-                self.monitored_tokens.contains(&v.token_address) &&
-                v.average_trade_size >= self.min_volume &&
-                v.average_trade_size <= self.max_volume &&
-                (v.trade_count + v.swap_count) >= 3
-            )
-            .cloned()
+    const TEST_MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    /// In-memory RPC node. Signatures are served newest first and honour
+    /// `before`/`until` like a real node; transactions without an entry fail.
+    #[derive(Clone, Default)]
+    pub(crate) struct FakeRpc {
+        url: String,
+        pub signatures: Arc<Mutex<HashMap<String, Vec<String>>>>,
+        pub transactions: Arc<Mutex<HashMap<String, Value>>>,
+        /// Decimals of the mints `getTokenSupply` answers for.
+        pub mints: Arc<Mutex<HashMap<String, u8>>>,
+        /// Data of the accounts `getAccountInfo` answers for.
+        pub accounts: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+        /// Programs whose `getSignaturesForAddress` requests fail.
+        pub failing_programs: Arc<Mutex<HashSet<String>>>,
+        /// Methods of every request received, in order.
+        pub requests: Arc<Mutex<Vec<String>>>,
+        /// Commitment of every request that set one, in order.
+        pub commitments: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FakeRpc {
+        pub fn new(url: &str) -> Self {
+            Self { url: url.to_string(), ..Self::default() }
+        }
+
+        pub fn client(&self) -> Arc<RpcClient> {
+            Arc::new(RpcClient::new_sender(self.clone(), RpcClientConfig::default()))
+        }
+
+        pub fn request_count(&self, method: &str) -> usize {
+            self.requests.lock().unwrap().iter().filter(|m| m.as_str() == method).count()
+        }
+
+        fn signatures_for(&self, params: &Value) -> Value {
+            let program = params[0].as_str().unwrap_or_default();
+            let before = params[1]["before"].as_str();
+            let until = params[1]["until"].as_str();
+            let limit = params[1]["limit"].as_u64().unwrap_or(1000) as usize;
+            let signatures = self.signatures.lock().unwrap().get(program).cloned().unwrap_or_default();
+            let start = before
+                .and_then(|before| signatures.iter().position(|s| s == before).map(|i| i + 1))
+                .unwrap_or(0);
+            let page: Vec<Value> = signatures[start.min(signatures.len())..]
+                .iter()
+                .take_while(|signature| Some(signature.as_str()) != until)
+                .take(limit)
+                .map(|signature| json!({ "signature": signature, "slot": 1, "err": null, "memo": null, "blockTime": null }))
+                .collect();
+            Value::Array(page)
+        }
+    }
+
+    #[async_trait]
+    impl RpcSender for FakeRpc {
+        async fn send(&self, request: RpcRequest, params: Value) -> Result<Value, ClientError> {
+            self.requests.lock().unwrap().push(request.to_string());
+            if let Some(commitment) = params[1]["commitment"].as_str() {
+                self.commitments.lock().unwrap().push(commitment.to_string());
+            }
+            match request {
+                RpcRequest::GetSignaturesForAddress => {
+                    let program = params[0].as_str().unwrap_or_default();
+                    if self.failing_programs.lock().unwrap().contains(program) {
+                        return Err(ClientErrorKind::Custom(format!("signatures for {} not available", program)).into());
+                    }
+                    Ok(self.signatures_for(&params))
+                }
+                RpcRequest::GetTransaction => {
+                    let signature = params[0].as_str().unwrap_or_default();
+                    self.transactions.lock().unwrap().get(signature).cloned().ok_or_else(|| {
+                        ClientErrorKind::Custom(format!("transaction {} not available", signature)).into()
+                    })
+                }
+                RpcRequest::GetTokenSupply => {
+                    let mint = params[0].as_str().unwrap_or_default();
+                    let decimals = self.mints.lock().unwrap().get(mint).copied().ok_or_else(|| {
+                        ClientError::from(ClientErrorKind::Custom(format!("{} is not a mint", mint)))
+                    })?;
+                    Ok(json!({
+                        "context": { "slot": 1 },
+                        "value": { "amount": "1", "decimals": decimals, "uiAmount": null, "uiAmountString": "1" },
+                    }))
+                }
+                RpcRequest::GetAccountInfo => {
+                    let address = params[0].as_str().unwrap_or_default();
+                    let account = self.accounts.lock().unwrap().get(address).map(|data| {
+                        let account = Account { lamports: 1, data: data.clone(), ..Account::default() };
+                        encode_ui_account(&Pubkey::from_str(address).unwrap(), &account, UiAccountEncoding::Base64, None, None)
+                    });
+                    Ok(json!({ "context": { "slot": 1 }, "value": account }))
+                }
+                RpcRequest::GetHealth => Ok(json!("ok")),
+                RpcRequest::GetSlot => Ok(json!(42)),
+                other => Err(ClientErrorKind::Custom(format!("{} not faked", other)).into()),
+            }
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            self.url.clone()
+        }
+    }
+
+    /// A distinct valid signature per `seed`.
+    pub(crate) fn signature(seed: u8) -> String {
+        Signature::from([seed; 64]).to_string()
+    }
+
+    /// `getTransaction` response moving `mint` from `pre` to `post` base units.
+    pub(crate) fn transaction(mint: &str, pre: u64, post: u64) -> Value {
+        let token_balance = |amount: u64| json!([{
+            "accountIndex": 1,
+            "mint": mint,
+            "uiTokenAmount": { "uiAmount": null, "decimals": 0, "amount": amount.to_string(), "uiAmountString": amount.to_string() },
+        }]);
+        json!({
+            "slot": 1,
+            "blockTime": null,
+            "transaction": "",
+            "meta": {
+                "err": null,
+                "status": { "Ok": null },
+                "fee": 0,
+                "preBalances": [],
+                "postBalances": [],
+                "preTokenBalances": token_balance(pre),
+                "postTokenBalances": token_balance(post),
+            },
+        })
+    }
+
+    fn tracker() -> VolumeTracker {
+        VolumeTracker::with_rpc_client(Arc::new(RpcClient::new_mock("succeeds".to_string())), 100.0, 10_000.0)
+    }
+
+    /// A tracker monitoring `TEST_MINT` at a cached price of $1, so balance
+    /// processing never reaches the price API.
+    fn monitoring_tracker() -> VolumeTracker {
+        let mut tracker = tracker();
+        tracker.monitored_tokens.insert(TEST_MINT.to_string());
+        tracker.token_names_cache.insert(TEST_MINT.to_string(), "BONK".to_string());
+        tracker.price_cache.insert(TEST_MINT, 1.0);
+        tracker
+    }
+
+    fn balance(account_index: u8, mint: &str, amount: u64) -> UiTransactionTokenBalance {
+        UiTransactionTokenBalance {
+            account_index,
+            mint: mint.to_string(),
+            ui_token_amount: UiTokenAmount {
+                ui_amount: None,
+                decimals: 0,
+                amount: amount.to_string(),
+                ui_amount_string: amount.to_string(),
+            },
+            owner: OptionSerializer::None,
+            program_id: OptionSerializer::None,
+        }
+    }
+
+    async fn process(
+        tracker: &mut VolumeTracker,
+        pre: &[UiTransactionTokenBalance],
+        post: &[UiTransactionTokenBalance]
+    ) -> Vec<TradingVolume> {
+        let mut volumes = Vec::new();
+        tracker.process_token_balances("sig", pre, post, false, &mut volumes).await.unwrap();
+        volumes
+    }
+
+    #[tokio::test]
+    async fn sub_minimum_balance_change_is_not_counted() {
+        let mut tracker = monitoring_tracker().with_minimum_transaction(MinimumTransaction::Raw(1_000));
+
+        let volumes = process(&mut tracker, &[balance(1, TEST_MINT, 0)], &[balance(1, TEST_MINT, 500)]).await;
+        assert!(volumes.is_empty());
+
+        let volumes = process(&mut tracker, &[balance(1, TEST_MINT, 0)], &[balance(1, TEST_MINT, 5_000)]).await;
+        assert_eq!(volumes.len(), 1);
+        assert_eq!(volumes[0].total_volume, 5_000.0);
+    }
+
+    #[tokio::test]
+    async fn sub_minimum_usd_value_is_not_counted() {
+        let mut tracker = monitoring_tracker().with_minimum_transaction(MinimumTransaction::Usd(1_000.0));
+
+        let volumes = process(&mut tracker, &[balance(1, TEST_MINT, 0)], &[balance(1, TEST_MINT, 500)]).await;
+        assert!(volumes.is_empty());
+    }
+
+    fn raydium() -> Pubkey {
+        Pubkey::from_str(RAYDIUM_AMM_PROGRAM).unwrap()
+    }
+
+    /// A `monitoring_tracker` on `rpc` that only tracks Raydium.
+    fn fake_tracker(rpc: &FakeRpc) -> VolumeTracker {
+        let mut tracker = monitoring_tracker().with_tracked_programs(vec![raydium()]);
+        tracker.rpc_endpoints = vec![RpcEndpoint::new(rpc.client())];
+        tracker
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("whale-bot-{}-{}.json", name, std::process::id()))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failed_transaction_is_skipped_and_cursor_still_advances() {
+        let rpc = FakeRpc::new("fake");
+        rpc.signatures.lock().unwrap().insert(RAYDIUM_AMM_PROGRAM.to_string(), vec![signature(3), signature(2), signature(1)]);
+        rpc.transactions.lock().unwrap().extend([
+            (signature(3), transaction(TEST_MINT, 0, 300)),
+            (signature(1), transaction(TEST_MINT, 0, 200)),
+        ]);
+        let mut tracker = fake_tracker(&rpc);
+
+        let volumes = tracker.track_trades().await.unwrap();
+
+        assert_eq!(volumes.len(), 1);
+        assert_eq!(volumes[0].total_volume, 500.0);
+        assert_eq!(tracker.cursor_for(&raydium()), Some(signature(3).as_str()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn restart_from_saved_cursor_does_not_reprocess() {
+        let cursor_file = temp_path("restart-cursor");
+        let rpc = FakeRpc::new("fake");
+        rpc.signatures.lock().unwrap().insert(RAYDIUM_AMM_PROGRAM.to_string(), vec![signature(2), signature(1)]);
+        rpc.transactions.lock().unwrap().extend([
+            (signature(2), transaction(TEST_MINT, 0, 300)),
+            (signature(1), transaction(TEST_MINT, 0, 200)),
+        ]);
+        fake_tracker(&rpc).with_cursor_file(&cursor_file).track_trades().await.unwrap();
+
+        rpc.signatures.lock().unwrap().get_mut(RAYDIUM_AMM_PROGRAM).unwrap().insert(0, signature(4));
+        rpc.transactions.lock().unwrap().insert(signature(4), transaction(TEST_MINT, 0, 700));
+        rpc.requests.lock().unwrap().clear();
+        let mut restarted = fake_tracker(&rpc).with_cursor_file(&cursor_file);
+        let volumes = restarted.track_trades().await.unwrap();
+        std::fs::remove_file(&cursor_file).unwrap();
+
+        assert_eq!(rpc.request_count("getTransaction"), 1);
+        assert_eq!(volumes[0].total_volume, 700.0);
+        assert_eq!(restarted.cursor_for(&raydium()), Some(signature(4).as_str()));
+    }
+
+    /// Metaplex metadata account bytes with NUL-padded name and symbol, as
+    /// stored on chain.
+    fn metadata_fixture(name: &str, symbol: &str) -> Vec<u8> {
+        let mut data = vec![4u8];
+        data.extend([1u8; 32]);
+        data.extend([2u8; 32]);
+        for (value, width) in [(name, 32), (symbol, 10), ("https://example.com", 200)] {
+            data.extend((width as u32).to_le_bytes());
+            let mut padded = value.as_bytes().to_vec();
+            padded.resize(width, 0);
+            data.extend(padded);
+        }
+        data
+    }
+
+    #[test]
+    fn metadata_address_is_the_metaplex_pda() {
+        // BONK's metadata account on mainnet.
+        let bonk_metadata = Pubkey::from_str("FDZZbyY9XGpL3CNKUZxLk3wFTTQYL3TkDiDzqxrizcPN").unwrap();
+        assert_eq!(VolumeTracker::metadata_address(&Pubkey::from_str(TEST_MINT).unwrap()), bonk_metadata);
+        assert_ne!(VolumeTracker::metadata_address(&Pubkey::from_str(USDC_MINT).unwrap()), bonk_metadata);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failed_name_lookup_is_retried() {
+        let rpc = FakeRpc::new("fake");
+        let mut tracker = tracker();
+        tracker.rpc_endpoints = vec![RpcEndpoint::new(rpc.client())];
+
+        assert_eq!(tracker.get_token_name(TEST_MINT).await.unwrap(), TEST_MINT);
+        assert!(!tracker.token_names_cache.contains_key(TEST_MINT));
+
+        let metadata_address = VolumeTracker::metadata_address(&Pubkey::from_str(TEST_MINT).unwrap());
+        rpc.accounts.lock().unwrap().insert(metadata_address.to_string(), metadata_fixture("Bonk", "BONK"));
+        assert_eq!(tracker.get_token_name(TEST_MINT).await.unwrap(), "BONK");
+        assert_eq!(tracker.get_token_name(TEST_MINT).await.unwrap(), "BONK");
+        assert_eq!(rpc.request_count("getAccountInfo"), 2);
+    }
+
+    #[test]
+    fn parse_metadata_name_reads_padded_fields() {
+        let data = metadata_fixture("Bonk", "BONK");
+        assert_eq!(VolumeTracker::parse_metadata_name(&data), Some(("Bonk".to_string(), "BONK".to_string())));
+        assert_eq!(VolumeTracker::parse_metadata_name(&data[..80]), None);
+    }
+
+    fn with_token_index(mut tracker: VolumeTracker, tokens: &[(&str, &str)]) -> VolumeTracker {
+        let index = tokens
+            .iter()
+            .map(|(symbol, address)| (symbol.to_string(), TokenInfo { symbol: symbol.to_string(), address: address.to_string() }))
             .collect();
+        tracker.token_index = Some((index, SystemTime::now()));
+        tracker
+    }
+
+    #[test]
+    fn failed_token_list_request_is_not_reported_as_not_found() {
+        let error = VolumeTracker::parse_token_list_response(reqwest::StatusCode::INTERNAL_SERVER_ERROR, "oops").unwrap_err();
+        assert!(matches!(&error, BotError::TokenLookup(message) if message.contains("500") && !message.contains("not found")));
+
+        let error = VolumeTracker::parse_token_list_response(reqwest::StatusCode::OK, r#"{"data":{}}"#).unwrap_err();
+        assert!(error.to_string().contains("missing data.mintList"));
+        assert!(VolumeTracker::parse_token_list_response(reqwest::StatusCode::OK, "<html>").is_err());
+
+        let body = json!({ "data": { "mintList": [
+            { "symbol": "bonk", "address": TEST_MINT },
+            { "symbol": "BONK", "address": USDC_MINT },
+        ] } });
+        let index = VolumeTracker::parse_token_list_response(reqwest::StatusCode::OK, &body.to_string()).unwrap();
+        assert_eq!(index["BONK"].address, TEST_MINT);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unlisted_mint_can_be_monitored() {
+        let rpc = FakeRpc::new("fake");
+        rpc.mints.lock().unwrap().insert(TEST_MINT.to_string(), 5);
+        let mut tracker = with_token_index(tracker(), &[("USDC", USDC_MINT)]);
+        tracker.rpc_endpoints = vec![RpcEndpoint::new(rpc.client())];
+
+        let listed = tracker.add_monitored_token(USDC_MINT).await.unwrap();
+        assert_eq!(listed.symbol, "USDC");
+
+        let unlisted = tracker.add_monitored_token(TEST_MINT).await.unwrap();
+        assert_eq!(unlisted.address, TEST_MINT);
+        assert_eq!(rpc.request_count("getAccountInfo"), 1);
+        assert!(tracker.monitored_tokens.contains(TEST_MINT));
+
+        assert!(tracker.add_monitored_token("NOTLISTED").await.is_err());
+        assert!(tracker.add_monitored_token(RAYDIUM_AMM_PROGRAM).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn decimals_are_fetched_once() {
+        let rpc = FakeRpc::new("fake");
+        rpc.mints.lock().unwrap().insert(TEST_MINT.to_string(), 5);
+        let mut tracker = fake_tracker(&rpc);
+
+        assert_eq!(tracker.get_token_decimals(TEST_MINT).unwrap(), 5);
+        assert_eq!(tracker.get_token_decimals(TEST_MINT).unwrap(), 5);
+        assert_eq!(rpc.request_count("getTokenSupply"), 1);
+    }
+
+    #[tokio::test]
+    async fn decimals_from_a_balance_entry_are_reused() {
+        let mut tracker = monitoring_tracker();
+        let mut post = balance(1, TEST_MINT, 250_000);
+        post.ui_token_amount.decimals = 2;
+
+        let volumes = process(&mut tracker, &[balance(1, TEST_MINT, 0)], &[post]).await;
+
+        assert_eq!(volumes[0].total_volume, 2_500.0);
+        assert_eq!(tracker.get_token_decimals(TEST_MINT).unwrap(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn poll_and_stream_modes_share_processing() {
+        let rpc = FakeRpc::new("fake");
+        rpc.signatures.lock().unwrap().insert(RAYDIUM_AMM_PROGRAM.to_string(), vec![signature(2), signature(1)]);
+        rpc.transactions.lock().unwrap().extend([
+            (signature(2), transaction(TEST_MINT, 0, 300)),
+            (signature(1), transaction(TEST_MINT, 500, 0)),
+        ]);
+
+        let polled = fake_tracker(&rpc).track_trades().await.unwrap();
+        let streamed = fake_tracker(&rpc)
+            .track_streamed_signatures(vec![(raydium(), signature(2)), (raydium(), signature(1))])
+            .await
+            .unwrap();
+
+        assert_eq!(polled.len(), 1);
+        assert_eq!(streamed.len(), 1);
+        for volume in [&polled[0], &streamed[0]] {
+            assert_eq!(volume.total_volume, 800.0);
+            assert_eq!(volume.swap_count, 2);
+            assert_eq!(volume.largest_trade, 500.0);
+        }
+    }
+
+    #[test]
+    fn full_stream_channel_counts_drops() {
+        let (sender, mut receiver) = SignatureSender::channel(2);
+        for seed in 0..5 {
+            assert!(sender.send(raydium(), signature(seed)));
+        }
+
+        assert_eq!(sender.take_dropped(), 3);
+        assert_eq!(sender.take_dropped(), 0);
+        assert_eq!(receiver.try_recv().unwrap().1, signature(0));
+        assert_eq!(receiver.try_recv().unwrap().1, signature(1));
+        assert!(receiver.try_recv().is_err());
+
+        drop(receiver);
+        assert!(!sender.send(raydium(), signature(9)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn two_programs_merge_without_double_counting() {
+        let rpc = FakeRpc::new("fake");
+        rpc.signatures.lock().unwrap().extend([
+            (RAYDIUM_AMM_PROGRAM.to_string(), vec![signature(2), signature(1)]),
+            // The aggregator swap routed through Raydium shows up under both.
+            (JUPITER_AGGREGATOR_PROGRAM.to_string(), vec![signature(3), signature(2)]),
+        ]);
+        rpc.transactions.lock().unwrap().extend([
+            (signature(1), transaction(TEST_MINT, 0, 100)),
+            (signature(2), transaction(TEST_MINT, 0, 200)),
+            (signature(3), transaction(TEST_MINT, 0, 400)),
+        ]);
+        let jupiter = Pubkey::from_str(JUPITER_AGGREGATOR_PROGRAM).unwrap();
+        let mut tracker = fake_tracker(&rpc).with_tracked_programs(vec![raydium(), jupiter]);
+
+        let volumes = tracker.track_trades().await.unwrap();
+
+        // Per program: one page of signatures and the empty page ending it.
+        assert_eq!(rpc.request_count("getSignaturesForAddress"), 4);
+        assert_eq!(rpc.request_count("getTransaction"), 3);
+        assert_eq!(volumes[0].total_volume, 700.0);
+        assert_eq!(volumes[0].swap_count, 3);
+        assert_eq!(tracker.cursor_for(&jupiter), Some(signature(3).as_str()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failing_program_does_not_drop_the_others() {
+        let rpc = FakeRpc::new("fake");
+        rpc.signatures.lock().unwrap().insert(RAYDIUM_AMM_PROGRAM.to_string(), vec![signature(2), signature(1)]);
+        rpc.failing_programs.lock().unwrap().insert(JUPITER_AGGREGATOR_PROGRAM.to_string());
+        rpc.transactions.lock().unwrap().extend([
+            (signature(1), transaction(TEST_MINT, 0, 100)),
+            (signature(2), transaction(TEST_MINT, 0, 200)),
+        ]);
+        let jupiter = Pubkey::from_str(JUPITER_AGGREGATOR_PROGRAM).unwrap();
+        let mut tracker = fake_tracker(&rpc).with_tracked_programs(vec![jupiter, raydium()]);
+
+        let volumes = tracker.track_trades().await.unwrap();
+
+        assert_eq!(volumes.len(), 1);
+        assert_eq!(volumes[0].total_volume, 300.0);
+        assert_eq!(tracker.cursor_for(&raydium()), Some(signature(2).as_str()));
+        assert_eq!(tracker.cursor_for(&jupiter), None);
+    }
+
+    #[test]
+    fn tracked_programs_parse_names_and_ids() {
+        assert_eq!(VolumeTracker::parse_tracked_program("Orca").unwrap().to_string(), ORCA_WHIRLPOOL_PROGRAM);
+        assert_eq!(VolumeTracker::parse_tracked_program(OPENBOOK_PROGRAM).unwrap().to_string(), OPENBOOK_PROGRAM);
+        assert!(VolumeTracker::parse_tracked_program("serum").is_err());
+        assert_eq!(tracker().tracked_programs(), &[raydium()]);
+    }
+
+    /// `monitoring_tracker` that also monitors USDC and SOL at $1 and $150.
+    fn swap_tracker() -> VolumeTracker {
+        let mut tracker = monitoring_tracker();
+        for (mint, price) in [(USDC_MINT, 1.0), (WSOL_MINT, 150.0)] {
+            tracker.monitored_tokens.insert(mint.to_string());
+            tracker.token_names_cache.insert(mint.to_string(), mint.to_string());
+            tracker.price_cache.insert(mint, price);
+        }
+        tracker
+    }
+
+    #[tokio::test]
+    async fn stable_to_stable_swap_is_ignored() {
+        let mut tracker = swap_tracker();
+        // The RPC lists the same accounts in a different order after the swap.
+        let pre = [balance(1, USDC_MINT, 5_000), balance(2, USDT_MINT, 0)];
+        let post = [balance(2, USDT_MINT, 5_000), balance(1, USDC_MINT, 0)];
+
+        assert!(tracker.is_stable_swap(&pre, &post));
+        assert!(process(&mut tracker, &pre, &post).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stable_to_sol_swap_is_kept_and_matched_by_account() {
+        let mut tracker = swap_tracker();
+        let pre = [balance(1, USDC_MINT, 3_000), balance(2, WSOL_MINT, 0)];
+        let post = [balance(2, WSOL_MINT, 20), balance(1, USDC_MINT, 0)];
+
+        assert!(!tracker.is_stable_swap(&pre, &post));
+        let volumes = process(&mut tracker, &pre, &post).await;
+        let volume_of = |mint: &str| volumes.iter().find(|v| v.token_address == mint).map(|v| v.total_volume);
+        assert_eq!(volume_of(USDC_MINT), Some(3_000.0));
+        assert_eq!(volume_of(WSOL_MINT), Some(3_000.0));
+    }
+
+    #[tokio::test]
+    async fn created_and_closed_accounts_count_from_zero() {
+        let mut tracker = swap_tracker();
+        let pre = [balance(1, USDC_MINT, 2_000)];
+        let post = [balance(2, WSOL_MINT, 10)];
+
+        let volumes = process(&mut tracker, &pre, &post).await;
+        assert_eq!(volumes.len(), 2);
+        assert!(volumes.iter().all(|v| v.total_volume >= 1_500.0));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unreachable_endpoint_fails_over_to_the_next() {
+        let rpc = FakeRpc::new("fallback");
+        let mut tracker = tracker();
+        tracker.rpc_endpoints = vec![
+            RpcEndpoint::new(Arc::new(RpcClient::new("http://127.0.0.1:1".to_string()))),
+            RpcEndpoint::new(rpc.client()),
+        ];
+
+        for _ in 1..RPC_FAILOVER_THRESHOLD {
+            let result = tracker.call_rpc(|rpc_client| Ok(rpc_client.get_slot()?));
+            assert!(matches!(result, Err(BotError::RpcUnavailable(_))));
+        }
+        let slot = tracker.call_rpc(|rpc_client| Ok(rpc_client.get_slot()?)).unwrap();
+
+        assert_eq!(slot, 42);
+        assert_eq!(tracker.rpc_client().url(), "fallback");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn request_errors_do_not_fail_over() {
+        let rpc = FakeRpc::new("primary");
+        let mut tracker = tracker();
+        tracker.rpc_endpoints = vec![RpcEndpoint::new(rpc.client()), RpcEndpoint::new(FakeRpc::new("fallback").client())];
+
+        for _ in 0..RPC_FAILOVER_THRESHOLD * 2 {
+            let result = tracker.call_rpc(|rpc_client| Ok(rpc_client.get_token_supply(&raydium())?));
+            assert!(matches!(result, Err(BotError::Rpc(_))));
+        }
+        assert_eq!(tracker.rpc_client().url(), "primary");
+    }
+
+    #[tokio::test]
+    async fn price_within_ttl_is_served_from_cache() {
+        let cache = PriceCache::new(Duration::from_secs(60));
+        cache.insert(WSOL_MINT, 150.0);
+        assert_eq!(cache.clone().get_sol_usd_price().await.unwrap(), 150.0);
+
+        let expired = PriceCache::new(Duration::ZERO);
+        expired.insert(WSOL_MINT, 150.0);
+        assert_eq!(expired.cached(WSOL_MINT), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reset_clears_history_and_next_cycle_starts_fresh() {
+        let rpc = FakeRpc::new("fake");
+        let mut tracker = fake_tracker(&rpc);
+        tracker.set_single_tx_alert_threshold(TEST_MINT.to_string(), Some(100.0));
+        for seed in 1..=2 {
+            rpc.signatures.lock().unwrap().insert(RAYDIUM_AMM_PROGRAM.to_string(), vec![signature(seed)]);
+            rpc.transactions.lock().unwrap().insert(signature(seed), transaction(TEST_MINT, 0, 500));
+            tracker.program_cursors.clear();
+            tracker.track_trades().await.unwrap();
+        }
+        assert_eq!(tracker.top_movers(5).len(), 1);
+        assert_eq!(tracker.large_trades.len(), 2);
+
+        assert_eq!(tracker.reset_volume(None), 1);
+        assert!(tracker.volume_history.is_empty());
+        assert!(tracker.large_trades.is_empty());
+        assert!(tracker.top_movers(5).is_empty());
+        assert_eq!(tracker.reset_volume(None), 0);
+
+        rpc.signatures.lock().unwrap().insert(RAYDIUM_AMM_PROGRAM.to_string(), vec![signature(3)]);
+        rpc.transactions.lock().unwrap().insert(signature(3), transaction(TEST_MINT, 0, 500));
+        tracker.track_trades().await.unwrap();
+        let history: Vec<f64> = tracker.volume_history[TEST_MINT].iter().map(|(_, volume)| *volume).collect();
+        assert_eq!(history, vec![500.0]);
+        assert!(tracker.top_movers(5).is_empty());
+    }
+
+    fn cycle_volume(token_address: &str, total_volume: f64) -> TradingVolume {
+        TradingVolume {
+            token_address: token_address.to_string(),
+            token_name: token_address.to_string(),
+            total_volume,
+            trade_count: 0,
+            swap_count: 1,
+            average_trade_size: total_volume,
+            largest_trade: total_volume,
+            last_update: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn cycles_older_than_the_time_window_age_out() {
+        let mut tracker = monitoring_tracker();
+        tracker.set_token_volume_threshold(TEST_MINT.to_string(), 100.0, 10_000.0, 5).unwrap();
+        let stale = SystemTime::now() - Duration::from_secs(6 * 60);
+        tracker.volume_history.insert(TEST_MINT.to_string(), VecDeque::from([(stale, 100.0)]));
+
+        tracker.record_cycle_volumes(&[cycle_volume(TEST_MINT, 300.0)]);
+        tracker.clean_old_data();
+
+        // The cycle from before the gap is gone, so there is nothing to compare.
+        assert_eq!(tracker.volume_history[TEST_MINT].len(), 1);
+        assert!(tracker.top_movers(5).is_empty());
+
+        tracker.set_token_volume_threshold(TEST_MINT.to_string(), 100.0, 10_000.0, 10).unwrap();
+        tracker.volume_history.get_mut(TEST_MINT).unwrap().push_front((stale, 100.0));
+        tracker.clean_old_data();
+        assert_eq!(tracker.top_movers(5)[0].change_pct, 200.0);
+    }
+
+    #[test]
+    fn zero_and_overflowing_timeframes_are_rejected() {
+        let mut tracker = tracker();
+        let defaults = tracker.default_threshold();
+        assert!(tracker.set_default_volume(50.0, 500.0, 0).is_err());
+        assert!(tracker.set_default_volume(50.0, 500.0, u64::MAX).is_err());
+        assert_eq!(tracker.default_threshold(), defaults);
+
+        assert!(tracker.set_token_volume_threshold(TEST_MINT.to_string(), 50.0, 500.0, u64::MAX / 60 + 1).is_err());
+        assert!(tracker.token_thresholds.is_empty());
+        tracker.set_token_volume_threshold(TEST_MINT.to_string(), 50.0, 500.0, u64::MAX / 60).unwrap();
+        assert_eq!(tracker.token_thresholds[TEST_MINT].time_window, Duration::from_secs(u64::MAX / 60 * 60));
+    }
+
+    #[test]
+    fn top_movers_rank_the_change_between_the_last_two_cycles() {
+        let mut tracker = swap_tracker();
+        tracker.record_cycle_volumes(&[cycle_volume(TEST_MINT, 100.0), cycle_volume(USDC_MINT, 200.0)]);
+        assert!(tracker.top_movers(5).is_empty());
 
-        info!("Found {} hot pairs", hot_pairs.len());
-        hot_pairs
+        tracker.record_cycle_volumes(&[
+            cycle_volume(TEST_MINT, 300.0),
+            cycle_volume(USDC_MINT, 100.0),
+            cycle_volume(WSOL_MINT, 50.0),
+        ]);
+        let movers = tracker.top_movers(5);
+
+        let ranked: Vec<&str> = movers.iter().map(|m| m.token_address.as_str()).collect();
+        assert_eq!(ranked, vec![WSOL_MINT, TEST_MINT, USDC_MINT]);
+        assert_eq!(movers[0].change_pct, f64::INFINITY);
+        assert_eq!((movers[1].previous_volume, movers[1].current_volume, movers[1].change_pct), (100.0, 300.0, 200.0));
+        assert_eq!(movers[1].token_name, "BONK");
+        assert_eq!(movers[2].change_pct, -50.0);
+        assert_eq!(tracker.top_movers(1).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn blacklisted_mint_is_refused_and_skipped() {
+        let mut tracker = with_token_index(tracker(), &[("BONK", TEST_MINT)]).with_blacklisted_mints([TEST_MINT.to_string()]);
+        let error = tracker.add_monitored_token("BONK").await.unwrap_err();
+        assert!(error.to_string().contains("is blacklisted"));
+        assert!(!tracker.monitored_tokens.contains(TEST_MINT));
+
+        // Even a mint still marked as monitored is not counted once blacklisted.
+        let mut tracker = monitoring_tracker();
+        tracker.blacklisted_mints.insert(TEST_MINT.to_string());
+        assert!(process(&mut tracker, &[balance(1, TEST_MINT, 0)], &[balance(1, TEST_MINT, 5_000)]).await.is_empty());
+
+        let mut tracker = monitoring_tracker();
+        tracker.blacklist_mint(TEST_MINT);
+        assert!(!tracker.monitored_tokens.contains(TEST_MINT));
+    }
+
+    #[test]
+    fn monitored_tokens_list_is_sorted_by_name() {
+        let mut tracker = tracker();
+        assert_eq!(tracker.get_monitored_tokens_list(), "No tokens monitored");
+        for (mint, name) in [(USDC_MINT, Some("USDC")), (WSOL_MINT, Some("SOL")), (USDT_MINT, None), (TEST_MINT, Some("BONK"))] {
+            tracker.monitored_tokens.insert(mint.to_string());
+            if let Some(name) = name {
+                tracker.token_names_cache.insert(mint.to_string(), name.to_string());
+            }
+        }
+
+        let expected = format!("BONK ({}), {}, SOL ({}), USDC ({})", TEST_MINT, USDT_MINT, WSOL_MINT, USDC_MINT);
+        for _ in 0..3 {
+            assert_eq!(tracker.get_monitored_tokens_list(), expected);
+        }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn new_tokens_inherit_updated_defaults() {
+        let mut tracker = with_token_index(tracker(), &[("BONK", TEST_MINT), ("USDC", USDC_MINT)]);
+        tracker.add_monitored_token("BONK").await.unwrap();
+        let original = tracker.default_threshold();
+
+        tracker.set_default_volume(50.0, 500.0, 15).unwrap();
+        tracker.add_monitored_token("USDC").await.unwrap();
+
+        let updated = VolumeThreshold { min_volume: 50.0, max_volume: 500.0, time_window: Duration::from_secs(15 * 60) };
+        assert_eq!(tracker.token_thresholds[USDC_MINT], updated);
+        assert_eq!(tracker.token_thresholds[TEST_MINT], original);
+        assert_ne!(original, updated);
+    }
+
+    #[tokio::test]
+    async fn malformed_amount_is_skipped_not_counted_as_zero() {
+        let mut tracker = monitoring_tracker();
+        let mut malformed = balance(1, TEST_MINT, 0);
+        malformed.ui_token_amount.amount = "not a number".to_string();
+
+        let volumes = process(&mut tracker, &[malformed.clone()], &[balance(1, TEST_MINT, 5_000)]).await;
+        assert!(volumes.is_empty());
+
+        // A well-formed account in the same transaction is still counted.
+        let pre = [malformed, balance(2, TEST_MINT, 0)];
+        let post = [balance(1, TEST_MINT, 5_000), balance(2, TEST_MINT, 300)];
+        let volumes = process(&mut tracker, &pre, &post).await;
+        assert_eq!(volumes.len(), 1);
+        assert_eq!(volumes[0].total_volume, 300.0);
+    }
+
+    #[tokio::test]
+    async fn single_large_trade_alerts_despite_low_trade_count() {
+        let mut tracker = monitoring_tracker();
+        tracker.set_single_tx_alert_threshold(TEST_MINT.to_string(), Some(2_000.0));
+
+        let volumes = process(&mut tracker, &[balance(1, TEST_MINT, 0)], &[balance(1, TEST_MINT, 5_000)]).await;
+
+        // One trade inside the volume band is too few for a hot pair alert.
+        assert_eq!(volumes.len(), 1);
+        assert_eq!(volumes[0].swap_count, 1);
+        assert!(WhaleBot::hot_pair_messages(volumes, 0.0).is_empty());
+        let trades = tracker.take_large_trades();
+        assert_eq!(trades.len(), 1);
+        assert_eq!((trades[0].token_name.as_str(), trades[0].value), ("BONK", 5_000.0));
+        assert_eq!(trades[0].signature, "sig");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn alerted_signature_is_not_announced_again_after_restart() {
+        let alerted_file = temp_path("alerted-signatures");
+        let rpc = FakeRpc::new("fake");
+        rpc.signatures.lock().unwrap().insert(RAYDIUM_AMM_PROGRAM.to_string(), vec![signature(1)]);
+        rpc.transactions.lock().unwrap().insert(signature(1), transaction(TEST_MINT, 0, 50_000));
+        let large_trade_tracker = |rpc: &FakeRpc| {
+            let mut tracker = fake_tracker(rpc).with_alerted_signatures_file(&alerted_file);
+            tracker.set_single_tx_alert_threshold(TEST_MINT.to_string(), Some(20_000.0));
+            tracker
+        };
+
+        let mut tracker = large_trade_tracker(&rpc);
+        tracker.track_trades().await.unwrap();
+        assert_eq!(tracker.take_large_trades()[0].signature, signature(1));
+
+        // Without a cursor file the restarted tracker sees the signature again.
+        let mut restarted = large_trade_tracker(&rpc);
+        restarted.track_trades().await.unwrap();
+        std::fs::remove_file(&alerted_file).unwrap();
+
+        assert_eq!(rpc.request_count("getTransaction"), 2);
+        assert!(restarted.take_large_trades().is_empty());
+    }
+
+    #[tokio::test]
+    async fn monitored_token_always_has_a_threshold() {
+        let mut tracker = with_token_index(tracker(), &[("BONK", TEST_MINT), ("USDC", USDC_MINT)])
+            .with_blacklisted_mints([USDC_MINT.to_string()]);
+        tracker.add_monitored_token("BONK").await.unwrap();
+        assert!(tracker.add_monitored_token("USDC").await.is_err());
+        assert!(tracker.add_monitored_token("NOTLISTED").await.is_err());
+
+        assert_eq!(tracker.monitored_tokens, HashSet::from([TEST_MINT.to_string()]));
+        for token in &tracker.monitored_tokens {
+            assert!(tracker.token_thresholds.contains_key(token));
+        }
+        assert!(!tracker.token_thresholds.contains_key(USDC_MINT));
+
+        tracker.remove_monitored_token(TEST_MINT);
+        assert!(tracker.token_thresholds.is_empty());
+    }
+
+    /// Serves `body` with `status` to every HTTP request on a local port,
+    /// returning the URL and a count of requests served.
+    async fn serve_http(status: &'static str, body: String) -> (String, Arc<AtomicU64>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mint/list", listener.local_addr().unwrap());
+        let served = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&served);
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 4096];
+                let _ = stream.read(&mut request).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status, body.len(), body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, served)
+    }
+
+    #[tokio::test]
+    async fn token_list_is_fetched_once_per_ttl() {
+        let body = json!({ "data": { "mintList": [{ "symbol": "BONK", "address": TEST_MINT }] } });
+        let (url, served) = serve_http("200 OK", body.to_string()).await;
+        let mut tracker = tracker();
+        tracker.token_list_url = url;
+
+        assert_eq!(tracker.get_token_info("bonk").await.unwrap().address, TEST_MINT);
+        assert_eq!(tracker.get_token_info("BONK").await.unwrap().address, TEST_MINT);
+        assert!(tracker.get_token_info("WIF").await.is_err());
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+
+        let expired = SystemTime::now() - TOKEN_LIST_TTL - Duration::from_secs(1);
+        tracker.token_index.as_mut().unwrap().1 = expired;
+        tracker.get_token_info("BONK").await.unwrap();
+        assert_eq!(served.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn token_list_server_error_is_not_reported_as_not_found() {
+        let (url, _) = serve_http("500 Internal Server Error", "oops".to_string()).await;
+        let mut tracker = tracker();
+        tracker.token_list_url = url;
+
+        let error = tracker.get_token_info("BONK").await.unwrap_err().to_string();
+        assert!(error.contains("500") && !error.contains("not found"), "{}", error);
+        assert!(tracker.token_index.is_none());
+    }
+
+    #[test]
+    fn token_index_skips_incomplete_entries() {
+        let tokens = vec![
+            json!({ "symbol": "wif", "address": USDC_MINT }),
+            json!({ "symbol": "NOADDRESS" }),
+            json!({ "address": TEST_MINT }),
+        ];
+        let index = VolumeTracker::build_token_index(&tokens);
+        assert_eq!(index.len(), 1);
+        assert_eq!(index["WIF"].symbol, "wif");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn commitment_change_applies_from_the_next_cycle() {
+        let rpc = FakeRpc::new("fake");
+        rpc.signatures.lock().unwrap().insert(RAYDIUM_AMM_PROGRAM.to_string(), vec![signature(1)]);
+        rpc.transactions.lock().unwrap().insert(signature(1), transaction(TEST_MINT, 0, 500));
+        let mut tracker = fake_tracker(&rpc);
+
+        assert!(tracker.set_commitment("processed").is_err());
+        assert!(tracker.set_commitment("bogus").is_err());
+        assert_eq!(tracker.commitment(), CommitmentConfig::confirmed());
+        tracker.track_trades().await.unwrap();
+        let commitments = rpc.commitments.lock().unwrap().clone();
+        assert!(!commitments.is_empty() && commitments.iter().all(|c| c == "confirmed"), "{:?}", commitments);
+
+        tracker.set_commitment(" Finalized ").unwrap();
+        rpc.commitments.lock().unwrap().clear();
+        rpc.signatures.lock().unwrap().get_mut(RAYDIUM_AMM_PROGRAM).unwrap().insert(0, signature(2));
+        rpc.transactions.lock().unwrap().insert(signature(2), transaction(TEST_MINT, 0, 500));
+        tracker.track_trades().await.unwrap();
+
+        let commitments = rpc.commitments.lock().unwrap().clone();
+        assert_eq!(rpc.request_count("getTransaction"), 2);
+        assert!(!commitments.is_empty());
+        assert!(commitments.iter().all(|c| c == "finalized"), "{:?}", commitments);
+    }
+}
@@ -0,0 +1,388 @@
+use crate::app::AlertRoutes;
+use crate::bot::error::BotError;
+use crate::bot::trading::{MinimumTransaction, VolumeTracker};
+use solana_program::pubkey::Pubkey;
+use serde::Deserialize;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+const DEFAULT_MIN_VOLUME: f64 = 5000.0;
+const DEFAULT_MAX_VOLUME: f64 = 10000.0;
+
+/// Complete bot configuration, read from an optional TOML file and then
+/// overridden by any of the documented environment variables that are set.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub telegram: TelegramConfig,
+    pub rpc: RpcConfig,
+    pub tracker: TrackerConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TelegramConfig {
+    pub bot_token: Option<String>,
+    pub chat_ids: Vec<i64>,
+    pub hot_pair_chat_ids: Option<Vec<i64>>,
+    pub large_trade_chat_ids: Option<Vec<i64>>,
+    pub batch_alerts: Option<bool>,
+    /// Restart the dispatcher after Telegram was unreachable this long.
+    pub idle_timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct RpcConfig {
+    pub url: String,
+    /// Endpoints to fail over to, in order, when `url` keeps failing.
+    pub fallback_urls: Vec<String>,
+    /// `poll` or `stream`.
+    pub monitor_mode: Option<String>,
+    pub poll_jitter_secs: Option<u64>,
+    /// Streamed signatures buffered between cycles in `stream` mode.
+    pub stream_channel_capacity: Option<usize>,
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        Self {
+            url: DEFAULT_RPC_URL.to_string(),
+            fallback_urls: Vec::new(),
+            monitor_mode: None,
+            poll_jitter_secs: None,
+            stream_channel_capacity: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct TrackerConfig {
+    pub min_volume: f64,
+    pub max_volume: f64,
+    pub blacklisted_mints: Vec<String>,
+    pub stable_mints: Option<Vec<String>>,
+    pub exclude_stable_swaps: Option<bool>,
+    pub price_cache_ttl_secs: Option<u64>,
+    pub cursor_file: Option<PathBuf>,
+    pub alerted_signatures_file: Option<PathBuf>,
+    /// Program ids, or `raydium`/`openbook`/`orca`/`jupiter`, scanned for
+    /// trades. Defaults to Raydium only.
+    pub tracked_programs: Option<Vec<String>>,
+    /// Smallest balance change counted as a trade, in base units. Mutually
+    /// exclusive with `min_transaction_usd`.
+    pub min_transaction_raw: Option<u64>,
+    /// Smallest balance change counted as a trade, in USD.
+    pub min_transaction_usd: Option<f64>,
+    /// Hot pairs below this confidence (0 to 1) are not alerted on.
+    pub min_alert_confidence: Option<f64>,
+}
+
+impl Default for TrackerConfig {
+    fn default() -> Self {
+        Self {
+            min_volume: DEFAULT_MIN_VOLUME,
+            max_volume: DEFAULT_MAX_VOLUME,
+            blacklisted_mints: Vec::new(),
+            stable_mints: None,
+            exclude_stable_swaps: None,
+            price_cache_ttl_secs: None,
+            cursor_file: None,
+            alerted_signatures_file: None,
+            tracked_programs: None,
+            min_transaction_raw: None,
+            min_transaction_usd: None,
+            min_alert_confidence: None,
+        }
+    }
+}
+
+impl Config {
+    /// Loads `path` when given, applies environment overrides and checks
+    /// that the required Telegram settings ended up present.
+    pub fn load(path: Option<&Path>) -> Result<Self, BotError> {
+        let mut config = match path {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+        config.apply_env_overrides(|name| std::env::var(name).ok())?;
+
+        if config.telegram.bot_token.is_none() {
+            return Err(BotError::Config("TELEGRAM_BOT_TOKEN or telegram.bot_token must be set".to_string()));
+        }
+        if config.telegram.chat_ids.is_empty() {
+            return Err(BotError::Config("TELEGRAM_CHAT_ID or telegram.chat_ids must be set".to_string()));
+        }
+        config.tracker.minimum_transaction()?;
+        config.tracker.tracked_programs()?;
+        if config.telegram.idle_timeout_secs == Some(0) {
+            return Err(BotError::Config("idle_timeout_secs must be at least 1".to_string()));
+        }
+        if config.rpc.stream_channel_capacity == Some(0) {
+            return Err(BotError::Config("stream_channel_capacity must be at least 1".to_string()));
+        }
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, BotError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| BotError::Config(format!("Failed to read {}: {}", path.display(), e)))?;
+        Self::from_toml(&contents)
+            .map_err(|e| BotError::Config(format!("Invalid config file {}: {}", path.display(), e)))
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// Overrides file values with the variables `env` returns, so tests can
+    /// supply their own instead of the process environment.
+    fn apply_env_overrides(&mut self, env: impl Fn(&str) -> Option<String>) -> Result<(), BotError> {
+        if let Some(token) = env("TELEGRAM_BOT_TOKEN") {
+            self.telegram.bot_token = Some(token);
+        }
+        if let Some(chat_ids) = env_chat_ids(&env, "TELEGRAM_CHAT_ID")? {
+            self.telegram.chat_ids = chat_ids;
+        }
+        if let Some(chat_ids) = env_chat_ids(&env, "TELEGRAM_HOT_PAIR_CHAT_IDS")? {
+            self.telegram.hot_pair_chat_ids = Some(chat_ids);
+        }
+        if let Some(chat_ids) = env_chat_ids(&env, "TELEGRAM_LARGE_TRADE_CHAT_IDS")? {
+            self.telegram.large_trade_chat_ids = Some(chat_ids);
+        }
+        if let Some(batch) = env_parse(&env, "BATCH_ALERTS")? {
+            self.telegram.batch_alerts = Some(batch);
+        }
+        if let Some(secs) = env_parse(&env, "TELEGRAM_IDLE_TIMEOUT_SECS")? {
+            self.telegram.idle_timeout_secs = Some(secs);
+        }
+
+        if let Some(url) = env("RPC_URL") {
+            self.rpc.url = url;
+        }
+        if let Some(urls) = env_list(&env, "RPC_FALLBACK_URLS") {
+            self.rpc.fallback_urls = urls;
+        }
+        if let Some(mode) = env("MONITOR_MODE") {
+            self.rpc.monitor_mode = Some(mode);
+        }
+        if let Some(secs) = env_parse(&env, "POLL_JITTER_SECS")? {
+            self.rpc.poll_jitter_secs = Some(secs);
+        }
+        if let Some(capacity) = env_parse(&env, "STREAM_CHANNEL_CAPACITY")? {
+            self.rpc.stream_channel_capacity = Some(capacity);
+        }
+
+        if let Some(mints) = env_list(&env, "BLACKLISTED_MINTS") {
+            self.tracker.blacklisted_mints = mints;
+        }
+        if let Some(mints) = env_list(&env, "STABLE_MINTS") {
+            self.tracker.stable_mints = Some(mints);
+        }
+        if let Some(exclude) = env_parse(&env, "EXCLUDE_STABLE_SWAPS")? {
+            self.tracker.exclude_stable_swaps = Some(exclude);
+        }
+        if let Some(secs) = env_parse(&env, "PRICE_CACHE_TTL_SECS")? {
+            self.tracker.price_cache_ttl_secs = Some(secs);
+        }
+        if let Some(path) = env("CURSOR_FILE") {
+            self.tracker.cursor_file = Some(path.into());
+        }
+        if let Some(path) = env("ALERTED_SIGNATURES_FILE") {
+            self.tracker.alerted_signatures_file = Some(path.into());
+        }
+        if let Some(programs) = env_list(&env, "TRACKED_PROGRAMS") {
+            self.tracker.tracked_programs = Some(programs);
+        }
+        if let Some(raw) = env_parse(&env, "MIN_TRANSACTION_RAW")? {
+            self.tracker.min_transaction_raw = Some(raw);
+        }
+        if let Some(usd) = env_parse(&env, "MIN_TRANSACTION_USD")? {
+            self.tracker.min_transaction_usd = Some(usd);
+        }
+        if let Some(confidence) = env_parse(&env, "MIN_ALERT_CONFIDENCE")? {
+            self.tracker.min_alert_confidence = Some(confidence);
+        }
+        Ok(())
+    }
+}
+
+impl TrackerConfig {
+    pub fn tracked_programs(&self) -> Result<Option<Vec<Pubkey>>, BotError> {
+        self.tracked_programs
+            .as_ref()
+            .map(|programs| {
+                if programs.is_empty() {
+                    return Err(BotError::Config("tracked_programs must list at least one program".to_string()));
+                }
+                programs
+                    .iter()
+                    .map(|program| VolumeTracker::parse_tracked_program(program).map_err(BotError::Config))
+                    .collect()
+            })
+            .transpose()
+    }
+
+    pub fn minimum_transaction(&self) -> Result<Option<MinimumTransaction>, BotError> {
+        match (self.min_transaction_raw, self.min_transaction_usd) {
+            (Some(_), Some(_)) => Err(BotError::Config(
+                "Set only one of min_transaction_raw and min_transaction_usd".to_string()
+            )),
+            (Some(raw), None) => Ok(Some(MinimumTransaction::Raw(raw))),
+            (None, Some(usd)) => Ok(Some(MinimumTransaction::Usd(usd))),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+fn env_parse<T>(env: impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<T>, BotError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    match env(name) {
+        Some(value) => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|e| BotError::Config(format!("Invalid {}: {}", name, e))),
+        None => Ok(None),
+    }
+}
+
+fn env_list(env: impl Fn(&str) -> Option<String>, name: &str) -> Option<Vec<String>> {
+    env(name).map(|values| {
+        values.split(',')
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect()
+    })
+}
+
+fn env_chat_ids(env: impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<Vec<i64>>, BotError> {
+    match env(name) {
+        Some(ids) => AlertRoutes::parse_chat_ids(&ids)
+            .map(|chat_ids| Some(chat_ids.into_iter().map(|chat_id| chat_id.0).collect()))
+            .map_err(BotError::Config),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::trading::{ORCA_WHIRLPOOL_PROGRAM, RAYDIUM_AMM_PROGRAM};
+    use std::collections::HashMap;
+
+    const FULL_CONFIG: &str = r#"
+        [telegram]
+        bot_token = "123:abc"
+        chat_ids = [1, -1002]
+        hot_pair_chat_ids = [-100123]
+        large_trade_chat_ids = [-100456]
+        batch_alerts = true
+        idle_timeout_secs = 600
+
+        [rpc]
+        url = "https://rpc.example.com"
+        fallback_urls = ["https://fallback.example.com"]
+        monitor_mode = "stream"
+        poll_jitter_secs = 5
+        stream_channel_capacity = 500
+
+        [tracker]
+        min_volume = 1000.0
+        max_volume = 2000.0
+        blacklisted_mints = ["mint1"]
+        stable_mints = ["stable1", "stable2"]
+        exclude_stable_swaps = false
+        price_cache_ttl_secs = 30
+        tracked_programs = ["raydium", "orca"]
+        cursor_file = "cursors.json"
+        alerted_signatures_file = "alerts.json"
+        min_transaction_raw = 1000
+        min_alert_confidence = 0.5
+    "#;
+
+    #[test]
+    fn full_config_populates_every_section() {
+        let config = Config::from_toml(FULL_CONFIG).unwrap();
+
+        assert_eq!(config.telegram.bot_token.as_deref(), Some("123:abc"));
+        assert_eq!(config.telegram.chat_ids, vec![1, -1002]);
+        assert_eq!(config.telegram.hot_pair_chat_ids, Some(vec![-100123]));
+        assert_eq!(config.telegram.large_trade_chat_ids, Some(vec![-100456]));
+        assert_eq!(config.telegram.batch_alerts, Some(true));
+        assert_eq!(config.telegram.idle_timeout_secs, Some(600));
+
+        assert_eq!(config.rpc.url, "https://rpc.example.com");
+        assert_eq!(config.rpc.fallback_urls, vec!["https://fallback.example.com"]);
+        assert_eq!(config.rpc.monitor_mode.as_deref(), Some("stream"));
+        assert_eq!(config.rpc.poll_jitter_secs, Some(5));
+        assert_eq!(config.rpc.stream_channel_capacity, Some(500));
+
+        let tracker = &config.tracker;
+        assert_eq!((tracker.min_volume, tracker.max_volume), (1000.0, 2000.0));
+        assert_eq!(tracker.blacklisted_mints, vec!["mint1"]);
+        assert_eq!(tracker.stable_mints, Some(vec!["stable1".to_string(), "stable2".to_string()]));
+        assert_eq!(tracker.exclude_stable_swaps, Some(false));
+        assert_eq!(tracker.price_cache_ttl_secs, Some(30));
+        assert_eq!(tracker.cursor_file, Some(PathBuf::from("cursors.json")));
+        assert_eq!(tracker.alerted_signatures_file, Some(PathBuf::from("alerts.json")));
+        assert_eq!(tracker.minimum_transaction().unwrap(), Some(MinimumTransaction::Raw(1000)));
+        assert_eq!(tracker.min_alert_confidence, Some(0.5));
+        let programs: Vec<String> = tracker.tracked_programs().unwrap().unwrap().iter().map(|p| p.to_string()).collect();
+        assert_eq!(programs, vec![RAYDIUM_AMM_PROGRAM, ORCA_WHIRLPOOL_PROGRAM]);
+    }
+
+    #[test]
+    fn empty_config_uses_defaults() {
+        let config = Config::from_toml("").unwrap();
+        assert_eq!(config.rpc.url, DEFAULT_RPC_URL);
+        assert_eq!(config.tracker.min_volume, DEFAULT_MIN_VOLUME);
+        assert!(config.telegram.chat_ids.is_empty());
+        assert!(Config::from_toml("[rpc]\nurl = 5\n").is_err());
+    }
+
+    fn env_from(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn env_overrides_replace_file_values() {
+        let mut config = Config::from_toml(FULL_CONFIG).unwrap();
+        config.apply_env_overrides(env_from(&[
+            ("TELEGRAM_CHAT_ID", "7,8"),
+            ("RPC_URL", "https://env.example.com"),
+            ("BATCH_ALERTS", "false"),
+            ("TRACKED_PROGRAMS", "jupiter"),
+            ("PRICE_CACHE_TTL_SECS", "90"),
+            ("MIN_ALERT_CONFIDENCE", "0.8"),
+        ])).unwrap();
+
+        assert_eq!(config.telegram.chat_ids, vec![7, 8]);
+        assert_eq!(config.rpc.url, "https://env.example.com");
+        assert_eq!(config.telegram.batch_alerts, Some(false));
+        assert_eq!(config.tracker.tracked_programs, Some(vec!["jupiter".to_string()]));
+        assert_eq!(config.tracker.price_cache_ttl_secs, Some(90));
+        assert_eq!(config.tracker.min_alert_confidence, Some(0.8));
+        assert_eq!(config.telegram.bot_token.as_deref(), Some("123:abc"));
+        assert_eq!(config.tracker.min_volume, 1000.0);
+
+        let invalid = Config::from_toml(FULL_CONFIG).unwrap().apply_env_overrides(env_from(&[("POLL_JITTER_SECS", "soon")]));
+        assert!(matches!(invalid, Err(BotError::Config(_))));
+    }
+
+    #[test]
+    fn minimum_transaction_accepts_one_unit() {
+        let config = Config::from_toml("[tracker]\nmin_transaction_usd = 250.0\n").unwrap();
+        assert_eq!(config.tracker.minimum_transaction().unwrap(), Some(MinimumTransaction::Usd(250.0)));
+
+        let config = Config::from_toml("[tracker]\nmin_transaction_raw = 1000\nmin_transaction_usd = 250.0\n").unwrap();
+        assert!(config.tracker.minimum_transaction().is_err());
+    }
+}
@@ -1,3 +1,5 @@
+pub mod app;
 pub mod bot;
+pub mod config;
 
 pub use bot::telegram::WhaleBot;
@@ -1,46 +1,102 @@
-use teloxide::prelude::*;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
-use log::{error, info};
-use std::error::Error;
-use std::fmt;
+use solana_client::rpc_client::RpcClient;
+use log::error;
+use teloxide::types::ChatId;
 
 // Import WhaleBot from your library
+use solana_whale_trader::app::{AlertKind, AlertRoutes};
+use solana_whale_trader::bot::error::BotError;
+use solana_whale_trader::config::Config;
 use solana_whale_trader::bot::telegram::WhaleBot;
-
-// Custom error type
-#[derive(Debug)]
-struct BotError(String);
-
-impl fmt::Display for BotError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
-    }
-}
-
-impl Error for BotError {}
+use solana_whale_trader::bot::trading::{MonitorMode, VolumeTracker};
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+async fn main() -> Result<(), BotError> {
     // Initialize logging
     env_logger::init();
 
-    // Read environment variables
-    let bot_token = std::env::var("TELEGRAM_BOT_TOKEN")
-        .expect("TELEGRAM_BOT_TOKEN must be set");
-    let chat_id = std::env::var("TELEGRAM_CHAT_ID")
-        .expect("TELEGRAM_CHAT_ID must be set")
-        .parse::<i64>()
-        .expect("Invalid TELEGRAM_CHAT_ID");
+    let config_path = config_path_from_args()?;
+    let config = Config::load(config_path.as_deref())?;
+
+    let to_chat_ids = |ids: &[i64]| ids.iter().copied().map(ChatId).collect::<Vec<_>>();
+    let mut alert_routes = AlertRoutes::new(to_chat_ids(&config.telegram.chat_ids));
+    if let Some(chat_ids) = &config.telegram.hot_pair_chat_ids {
+        alert_routes = alert_routes.with_route(AlertKind::HotPair, to_chat_ids(chat_ids));
+    }
+    if let Some(chat_ids) = &config.telegram.large_trade_chat_ids {
+        alert_routes = alert_routes.with_route(AlertKind::LargeTrade, to_chat_ids(chat_ids));
+    }
+
+    let monitor_mode = match &config.rpc.monitor_mode {
+        Some(mode) => mode.parse::<MonitorMode>().map_err(BotError::Config)?,
+        None => MonitorMode::default(),
+    };
+
+    // The primary client, shared by the tracker and the /diagnostics probe;
+    // fallback endpoints each get their own
+    let rpc_client = Arc::new(RpcClient::new(config.rpc.url.clone()));
+    let tracker_config = config.tracker;
+    let minimum_transaction = tracker_config.minimum_transaction()?;
+    let tracked_programs = tracker_config.tracked_programs()?;
+    let mut volume_tracker = VolumeTracker::with_rpc_client(
+        rpc_client,
+        tracker_config.min_volume,
+        tracker_config.max_volume
+    )
+    .with_fallback_rpc_urls(config.rpc.fallback_urls)
+    .with_blacklisted_mints(tracker_config.blacklisted_mints);
+    if let Some(mints) = tracker_config.stable_mints {
+        volume_tracker = volume_tracker.with_stable_mints(mints);
+    }
+    if let Some(exclude) = tracker_config.exclude_stable_swaps {
+        volume_tracker = volume_tracker.with_stable_swap_exclusion(exclude);
+    }
+    if let Some(path) = tracker_config.cursor_file {
+        volume_tracker = volume_tracker.with_cursor_file(path);
+    }
+    if let Some(path) = tracker_config.alerted_signatures_file {
+        volume_tracker = volume_tracker.with_alerted_signatures_file(path);
+    }
+    if let Some(secs) = tracker_config.price_cache_ttl_secs {
+        volume_tracker = volume_tracker.with_price_cache_ttl(Duration::from_secs(secs));
+    }
+    if let Some(programs) = tracked_programs {
+        volume_tracker = volume_tracker.with_tracked_programs(programs);
+    }
+    if let Some(minimum) = minimum_transaction {
+        volume_tracker = volume_tracker.with_minimum_transaction(minimum);
+    }
+    if let Some(confidence) = tracker_config.min_alert_confidence {
+        volume_tracker.set_min_alert_confidence(confidence).map_err(BotError::Config)?;
+    }
 
-    // Create and start the bot
-    let whale_bot = WhaleBot::new(&bot_token, chat_id)
-        .await
-        .map_err(|e| BotError(e.to_string()))?;
+    let bot_token = config.telegram.bot_token.unwrap_or_default();
+    let mut whale_bot = WhaleBot::with_tracker(&bot_token, alert_routes, volume_tracker)
+        .await?
+        .with_monitor_mode(monitor_mode);
+    if let Some(secs) = config.rpc.poll_jitter_secs {
+        whale_bot = whale_bot.with_poll_jitter(Duration::from_secs(secs));
+    }
+    if let Some(capacity) = config.rpc.stream_channel_capacity {
+        whale_bot = whale_bot.with_stream_channel_capacity(capacity);
+    }
+    if let Some(batch) = config.telegram.batch_alerts {
+        whale_bot = whale_bot.with_batch_alerts(batch);
+    }
+    if let Some(secs) = config.telegram.idle_timeout_secs {
+        whale_bot = whale_bot.with_idle_timeout(Duration::from_secs(secs));
+    }
 
     // Implement a robust main loop with restart capability
     loop {
         match whale_bot.start().await {
             Ok(_) => break,
+            Err(e) if e.is_fatal() => {
+                error!("Bot stopped on a permanent error: {}", e);
+                return Err(e);
+            }
             Err(e) => {
                 error!("Bot encountered an error: {}. Restarting...", e);
                 tokio::time::sleep(Duration::from_secs(5)).await;
@@ -49,4 +105,21 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     }
 
     Ok(())
+}
+
+/// Reads `--config <path>` (or `--config=<path>`) from the command line.
+fn config_path_from_args() -> Result<Option<PathBuf>, BotError> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args
+                .next()
+                .map(|path| Some(PathBuf::from(path)))
+                .ok_or_else(|| BotError::Config("--config requires a file path".to_string()));
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Ok(Some(PathBuf::from(path)));
+        }
+    }
+    Ok(None)
 }
\ No newline at end of file